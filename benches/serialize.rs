@@ -0,0 +1,54 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use serde_json::{json, Value};
+
+/// An object nested `depth` levels deep, with a few leaves at every level.
+fn deep_document(depth: usize) -> Value {
+    (0..depth).fold(json!({}), |inner, i| {
+        json!({ "id": i, "name": format!("level-{i}"), "tags": ["a", "b"], "child": inner })
+    })
+}
+
+/// An array of `len` small records, similar to a typical API response.
+fn wide_document(len: usize) -> Value {
+    Value::Array(
+        (0..len)
+            .map(|i| {
+                json!({
+                    "id": i,
+                    "user-name": format!("user{i}"),
+                    "active": i % 2 == 0,
+                    "address": { "city": "Tokyo", "zip": "100-0001" },
+                })
+            })
+            .collect(),
+    )
+}
+
+fn bench_documents(c: &mut Criterion) {
+    let mut group = c.benchmark_group("to_writer");
+    for (name, value) in [
+        ("deep_500", deep_document(500)),
+        ("wide_10000", wide_document(10_000)),
+    ] {
+        let len = serde_gron::to_string(&value).unwrap().len();
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_function(name, |b| {
+            let mut writer = Vec::with_capacity(len);
+            b.iter(|| {
+                writer.clear();
+                serde_gron::to_writer(black_box(&value), &mut writer).unwrap();
+            })
+        });
+        group.bench_function(format!("{name}_value"), |b| {
+            let mut writer = Vec::with_capacity(len);
+            b.iter(|| {
+                writer.clear();
+                serde_gron::value_to_writer(black_box(&value), &mut writer).unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_documents);
+criterion_main!(benches);
@@ -0,0 +1,276 @@
+//! Column-aligned output, e.g. `json.name = "a";` above `json.id   = 1;`.
+
+use serde_json::Value;
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt::Display, mem};
+
+use crate::{ser::Formatter, write::FmtWriter, Error, NamespaceKey, Write};
+
+/// Which statements [`Aligned`] pads to the same key width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    /// Each run of consecutive statements whose keys have the same parent, such as the
+    /// members of an object that holds no containers.
+    Container,
+    /// Each block of this many statements.
+    Window(usize),
+}
+
+/// Wraps a formatter to pad keys so the delimiters of a group of statements line up.
+///
+/// Statements are buffered until their group is complete. Keys are measured in characters,
+/// ignoring ANSI escape sequences, so colored output aligns too.
+#[derive(Debug)]
+pub struct Aligned<F> {
+    inner: F,
+    alignment: Alignment,
+    /// The start and end in the key buffer of each segment that may still be in it.
+    segments: Vec<(usize, usize)>,
+    /// Receives everything the inner formatter writes within a statement.
+    buf: FmtWriter<String>,
+    key: String,
+    delimiter: String,
+    /// The rendered parent of the statements in `pending`.
+    parent: Option<String>,
+    pending: Vec<Line>,
+}
+
+#[derive(Debug)]
+struct Line {
+    key: String,
+    delimiter: String,
+    rest: String,
+}
+
+impl<F> Aligned<F> {
+    pub fn new(inner: F, alignment: Alignment) -> Self {
+        Aligned {
+            inner,
+            alignment,
+            segments: Vec::new(),
+            buf: FmtWriter(String::new()),
+            key: String::new(),
+            delimiter: String::new(),
+            parent: None,
+            pending: Vec::new(),
+        }
+    }
+
+    fn take_buf(&mut self) -> String {
+        mem::take(&mut self.buf.0)
+    }
+
+    fn flush<W: Write>(&mut self, writer: &mut W) -> Result<(), Error> {
+        let width = self
+            .pending
+            .iter()
+            .map(|line| display_width(&line.key))
+            .max()
+            .unwrap_or_default();
+        for line in self.pending.drain(..) {
+            writer.write_str(&line.key)?;
+            for _ in display_width(&line.key)..width {
+                writer.write_str(" ")?;
+            }
+            writer.write_str(&line.delimiter)?;
+            writer.write_str(&line.rest)?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts the characters of `s` outside ANSI escape sequences.
+fn display_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+impl<W, F> Formatter<W> for Aligned<F>
+where
+    W: Write,
+    F: Formatter<W> + Formatter<FmtWriter<String>>,
+{
+    fn write_root(&mut self, key: &mut String, ns_root: &str) {
+        Formatter::<W>::write_root(&mut self.inner, key, ns_root)
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        // The serializer truncates the buffer when it leaves a segment, so any segment that
+        // ends past the current length has been left.
+        let start = key.len();
+        self.segments.retain(|&(_, end)| end <= start);
+        Formatter::<W>::write_key_segment(&mut self.inner, key, ns);
+        self.segments.push((start, key.len()));
+    }
+
+    fn begin_document(&mut self, writer: &mut W) -> Result<(), Error> {
+        Formatter::<W>::begin_document(&mut self.inner, writer)
+    }
+
+    fn end_document(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.flush(writer)?;
+        Formatter::<W>::end_document(&mut self.inner, writer)
+    }
+
+    fn begin_statement(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.inner.begin_statement(&mut self.buf)
+    }
+
+    fn end_statement(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.inner.end_statement(&mut self.buf)?;
+        let line = Line {
+            key: mem::take(&mut self.key),
+            delimiter: mem::take(&mut self.delimiter),
+            rest: self.take_buf(),
+        };
+        self.pending.push(line);
+        if let Alignment::Window(n) = self.alignment {
+            if self.pending.len() >= n {
+                self.flush(writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_key(&mut self, writer: &mut W, key: &str) -> Result<(), Error> {
+        self.inner.write_key(&mut self.buf, key)?;
+        self.key = self.take_buf();
+
+        if self.alignment == Alignment::Container {
+            let parent = self
+                .segments
+                .iter()
+                .rev()
+                .find(|&&(_, end)| end == key.len())
+                .map(|&(start, _)| String::from(&key[..start]));
+            if parent != self.parent {
+                self.flush(writer)?;
+                self.parent = parent;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_key_value_delimiter(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.inner.write_key_value_delimiter(&mut self.buf)?;
+        self.delimiter = self.take_buf();
+        Ok(())
+    }
+
+    fn write_end_of_line(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.inner.write_end_of_line(&mut self.buf)
+    }
+
+    fn write_null(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.inner.write_null(&mut self.buf)
+    }
+
+    fn write_bool(&mut self, _writer: &mut W, value: bool) -> Result<(), Error> {
+        self.inner.write_bool(&mut self.buf, value)
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        _writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        self.inner.write_number(&mut self.buf, value)
+    }
+
+    fn write_string(&mut self, _writer: &mut W, value: &str) -> Result<(), Error> {
+        self.inner.write_string(&mut self.buf, value)
+    }
+
+    fn write_init_array(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.inner.write_init_array(&mut self.buf)
+    }
+
+    fn write_init_object(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.inner.write_init_object(&mut self.buf)
+    }
+
+    fn write_json(&mut self, _writer: &mut W, value: &Value) -> Result<(), Error> {
+        self.inner.write_json(&mut self.buf, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{to_string_with_options, FormatType, Options};
+
+    fn value() -> Value {
+        json!({ "id": 1, "name": "a", "owner": { "id": 2, "login": "b" }, "tags": ["x"] })
+    }
+
+    #[test]
+    fn test_align_container() {
+        let options = Options {
+            align: Some(Alignment::Container),
+            ..Options::default()
+        };
+        assert_eq!(
+            to_string_with_options(&value(), &options).unwrap(),
+            r#"json = {};
+json.id    = 1;
+json.name  = "a";
+json.owner = {};
+json.owner.id    = 2;
+json.owner.login = "b";
+json.tags = [];
+json.tags[0] = "x";
+"#
+        );
+    }
+
+    #[test]
+    fn test_align_window() {
+        let options = Options {
+            align: Some(Alignment::Window(3)),
+            leaf_only: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            to_string_with_options(&value(), &options).unwrap(),
+            r#"json.id       = 1;
+json.name     = "a";
+json.owner.id = 2;
+json.owner.login = "b";
+json.tags[0]     = "x";
+"#
+        );
+    }
+
+    #[test]
+    fn test_align_colored() {
+        let options = Options {
+            align: Some(Alignment::Container),
+            format_type: FormatType::Color,
+            ..Options::default()
+        };
+        let colored = to_string_with_options(&json!({ "a": 1, "bcd": 2 }), &options).unwrap();
+        let lines: Vec<_> = colored.lines().skip(1).collect();
+        assert_eq!(
+            lines.iter().map(|l| display_width(l)).collect::<Vec<_>>(),
+            [13, 13]
+        );
+        assert!(lines[0].contains("\x1b[34ma\x1b[0m   = "));
+    }
+}
@@ -0,0 +1,292 @@
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use alloc::{vec, vec::Vec};
+
+use crate::{de::parse_str, ser::NamespaceKey, Error, Path, Statement};
+
+/// Applies gron statements to an existing value in order.
+///
+/// Intermediate objects and arrays are created as needed, and arrays are padded with `null`
+/// when an index past the end is assigned. Assigning `{}` or `[]` to a container of the same
+/// type leaves it as is, so the initialisation lines of gron output are harmless; use
+/// `delete` to clear a container first. Deleting a path that does not exist does nothing.
+///
+/// So that untrusted input cannot make arrays grow without bounds, the arrays may grow by at
+/// most 1024 elements more than there are statements in total; beyond that,
+/// [`Error::SparseArray`] is returned.
+pub fn apply<I>(value: &mut Value, statements: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = Statement>,
+{
+    let statements: Vec<_> = statements.into_iter().collect();
+    let mut growth = statements.len() + MAX_PADDING;
+    for statement in statements {
+        match statement {
+            Statement::Assign(path, v) => assign(value, &path, v, &mut growth)?,
+            Statement::Delete(path) => delete(value, &path),
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses gron text and applies it to `value`. See [`apply`].
+pub fn apply_str(value: &mut Value, s: &str) -> Result<(), Error> {
+    apply(value, parse_str(s)?)
+}
+
+/// Rebuilds a value from path/value pairs, such as those yielded by
+/// [`to_statements`](crate::to_statements), in any order.
+///
+/// Missing containers are created from the shape of the paths, and array indexes that are
+/// never assigned are filled with `null`. As in [`apply`], the arrays may hold at most 1024
+/// elements more than there are pairs. That limit is on the finished arrays, so it does not
+/// depend on the order of the pairs. Root names are ignored.
+///
+/// ```
+/// use serde_gron::{NamespaceKey, Path};
+/// use serde_json::json;
+///
+/// let value = serde_gron::unflatten([
+///     (Path::with_keys("json", vec![NamespaceKey::Array(1)]), json!("b")),
+///     (Path::new("json"), json!([])),
+/// ])
+/// .unwrap();
+/// assert_eq!(value, json!([null, "b"]));
+/// ```
+pub fn unflatten<I>(pairs: I) -> Result<Value, Error>
+where
+    I: IntoIterator<Item = (Path, Value)>,
+{
+    let pairs: Vec<_> = pairs.into_iter().collect();
+    let mut growth = pairs.len() + MAX_PADDING;
+    let mut value = Value::Null;
+    for (path, v) in pairs {
+        assign(&mut value, &path, v, &mut growth)?;
+    }
+
+    Ok(value)
+}
+
+/// Deserializes a `T` from path/value pairs. See [`unflatten`].
+pub fn from_pairs<T, I>(pairs: I) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (Path, Value)>,
+{
+    serde_json::from_value(unflatten(pairs)?).map_err(Error::Deserialize)
+}
+
+/// How many more array elements than statements or pairs may be created.
+pub(crate) const MAX_PADDING: usize = 1024;
+
+/// Assigns `value` at `path`, taking the elements added to arrays out of `growth`.
+///
+/// Growing an array to a given length costs the same whether it happens at once or one index
+/// at a time, so the total does not depend on the order of the assignments.
+fn assign(target: &mut Value, path: &Path, value: Value, growth: &mut usize) -> Result<(), Error> {
+    let mut current = target;
+    for (i, key) in path.keys.iter().enumerate() {
+        let prefix = || Path::with_keys(&*path.root, path.keys[..=i].to_vec());
+        if let (NamespaceKey::Array(n), Value::Null | Value::Array(_)) = (key, &*current) {
+            let len = current.as_array().map_or(0, Vec::len);
+            if *n >= len {
+                let added = (n - len).saturating_add(1);
+                *growth = growth
+                    .checked_sub(added)
+                    .ok_or_else(|| Error::SparseArray(prefix()))?;
+            }
+        }
+        current = child_or_insert(current, key).ok_or_else(|| Error::PathConflict(prefix()))?;
+    }
+
+    match (&*current, &value) {
+        (Value::Array(_), Value::Array(v)) if v.is_empty() => {}
+        (Value::Object(_), Value::Object(v)) if v.is_empty() => {}
+        _ => *current = value,
+    }
+
+    Ok(())
+}
+
+fn child_or_insert<'v>(value: &'v mut Value, key: &NamespaceKey) -> Option<&'v mut Value> {
+    match key {
+        NamespaceKey::Object(k) => {
+            if value.is_null() {
+                *value = Value::Object(Map::new());
+            }
+            let object = value.as_object_mut()?;
+            Some(object.entry(k.as_str()).or_insert(Value::Null))
+        }
+        NamespaceKey::Array(n) => {
+            if value.is_null() {
+                *value = Value::Array(vec![]);
+            }
+            let array = value.as_array_mut()?;
+            if array.len() <= *n {
+                array.resize(n + 1, Value::Null);
+            }
+            Some(&mut array[*n])
+        }
+    }
+}
+
+fn delete(target: &mut Value, path: &Path) {
+    let Some((last, parents)) = path.keys.split_last() else {
+        *target = Value::Null;
+        return;
+    };
+
+    let mut current = target;
+    for key in parents {
+        let child = match key {
+            NamespaceKey::Object(k) => current.get_mut(k.as_str()),
+            NamespaceKey::Array(n) => current.get_mut(n),
+        };
+        match child {
+            Some(child) => current = child,
+            None => return,
+        }
+    }
+
+    match (current, last) {
+        (Value::Object(object), NamespaceKey::Object(k)) => {
+            object.remove(k);
+        }
+        (Value::Array(array), NamespaceKey::Array(n)) if *n < array.len() => {
+            array.remove(*n);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::{String, ToString};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_creates_intermediates() {
+        let mut value = json!({ "a": 1 });
+        apply_str(&mut value, "json.b.c[2] = \"x\";").unwrap();
+        assert_eq!(value, json!({ "a": 1, "b": { "c": [null, null, "x"] } }));
+    }
+
+    #[test]
+    fn test_apply_full_output() {
+        let original = json!({ "a": [1, { "b": 2 }], "c": "d" });
+        let mut value = original.clone();
+        apply_str(&mut value, &crate::to_string(&original).unwrap()).unwrap();
+        assert_eq!(value, original);
+
+        let mut value = Value::Null;
+        apply_str(&mut value, &crate::to_string(&original).unwrap()).unwrap();
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn test_apply_delete() {
+        let mut value = json!({ "a": [1, 2, 3], "b": { "c": 1 } });
+        apply_str(
+            &mut value,
+            "delete json.a[1];\ndelete json.b.c;\ndelete json.x.y;",
+        )
+        .unwrap();
+        assert_eq!(value, json!({ "a": [1, 3], "b": {} }));
+    }
+
+    #[test]
+    fn test_apply_conflict() {
+        let mut value = json!({ "a": 1 });
+        assert!(matches!(
+            apply_str(&mut value, "json.a.b = 1;"),
+            Err(Error::PathConflict(path)) if path.to_string() == "json.a.b"
+        ));
+    }
+
+    #[test]
+    fn test_apply_rejects_distant_index() {
+        for s in [
+            "json = [];\njson[18446744073709551615] = 1;",
+            "json.a[4000000000000] = 1;",
+            "json[1025] = 1;",
+        ] {
+            let mut value = Value::Null;
+            assert!(
+                matches!(apply_str(&mut value, s), Err(Error::SparseArray(_))),
+                "{s}"
+            );
+        }
+
+        let mut value = json!([1]);
+        apply_str(&mut value, "json[1025] = 2;").unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 1026);
+    }
+
+    #[test]
+    fn test_unflatten_any_order() {
+        let original = json!({ "a": [{ "b": 1 }, [], "c"], "d": {}, "e": null });
+        let mut pairs: Vec<_> = crate::value_to_statements(original.clone())
+            .map(|s| match s {
+                Statement::Assign(path, v) => (path, v),
+                Statement::Delete(_) => unreachable!(),
+            })
+            .collect();
+        pairs.reverse();
+        assert_eq!(unflatten(pairs.clone()).unwrap(), original);
+
+        // Without init statements, empty containers are lost and their array slots are padded.
+        let leaves = pairs
+            .into_iter()
+            .filter(|(_, v)| !v.is_array() && !v.is_object());
+        assert_eq!(
+            unflatten(leaves).unwrap(),
+            json!({ "a": [{ "b": 1 }, null, "c"], "e": null })
+        );
+    }
+
+    #[test]
+    fn test_unflatten_reversed_long_array() {
+        let original = Value::Array((0..2000).map(Value::from).collect());
+        let mut pairs: Vec<_> = crate::value_to_statements(original.clone())
+            .map(|s| match s {
+                Statement::Assign(path, v) => (path, v),
+                Statement::Delete(_) => unreachable!(),
+            })
+            .collect();
+        pairs.reverse();
+        assert_eq!(unflatten(pairs.clone()).unwrap(), original);
+
+        // With only the last 500 elements, 1500 of the 2000 would be padding.
+        pairs.truncate(500);
+        assert!(matches!(
+            unflatten(pairs),
+            Err(Error::SparseArray(path)) if path.to_string() == "json[1999]"
+        ));
+    }
+
+    #[test]
+    fn test_from_pairs_sparse() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Config {
+            hosts: Vec<Option<String>>,
+        }
+
+        let hosts = |n| {
+            Path::with_keys(
+                "json",
+                vec![NamespaceKey::Object("hosts".into()), NamespaceKey::Array(n)],
+            )
+        };
+        let config: Config = from_pairs([(hosts(2), json!("c")), (hosts(0), json!("a"))]).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                hosts: vec![Some("a".into()), None, Some("c".into())]
+            }
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! Async counterparts of the writer and reader functions, built on tokio's I/O traits.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    apply::apply,
+    de::parse_line,
+    ser::{ColorFormatter, Formatter, NamespaceKey, RegularFormatter, Serializer},
+    write::FmtWriter,
+    Aligned, Error, FormatType, Options,
+};
+
+/// Output is handed to the async writer in chunks of about this size.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+pub async fn to_async_writer<W>(value: &impl Serialize, writer: &mut W) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    to_async_writer_with(value, writer, "json", FormatType::Regular).await
+}
+
+pub async fn to_async_writer_with<W>(
+    value: &impl Serialize,
+    writer: &mut W,
+    root_name: impl Into<String>,
+    format_type: FormatType,
+) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    to_async_writer_with_options(value, writer, &Options::new(root_name, format_type)).await
+}
+
+/// Writes `value` to `writer`, handing over each chunk of output as soon as it fills up.
+///
+/// A `Serialize` implementation cannot pause while the writer catches up, so `value` is first
+/// converted to a `serde_json::Value`; the output is never held in memory as a whole. Struct
+/// fields therefore come out in the order of a `serde_json::Map`, which is sorted unless
+/// serde_json's `preserve_order` feature is enabled. Use [`value_to_async_writer_with_options`]
+/// if you already have a `serde_json::Value`.
+pub async fn to_async_writer_with_options<W>(
+    value: &impl Serialize,
+    writer: &mut W,
+    options: &Options,
+) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    let value = serde_json::to_value(value).map_err(Error::Serialize)?;
+    value_to_async_writer_with_options(&value, writer, options).await
+}
+
+pub async fn value_to_async_writer<W>(value: &Value, writer: &mut W) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    value_to_async_writer_with(value, writer, "json", FormatType::Regular).await
+}
+
+pub async fn value_to_async_writer_with<W>(
+    value: &Value,
+    writer: &mut W,
+    root_name: impl Into<String>,
+    format_type: FormatType,
+) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    value_to_async_writer_with_options(value, writer, &Options::new(root_name, format_type)).await
+}
+
+/// Writes a `serde_json::Value` to `writer`, handing over each chunk as soon as it fills up.
+pub async fn value_to_async_writer_with_options<W>(
+    value: &Value,
+    writer: &mut W,
+    options: &Options,
+) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    // An `AsyncWrite` cannot say whether it is a terminal, so `FormatType::Auto` is plain.
+    if options.format_type.is_colored(false) {
+        let formatter = ColorFormatter::new(options.syntax, options.theme);
+        write_formatted(value, writer, formatter, options).await
+    } else {
+        let formatter = RegularFormatter::with_syntax(options.syntax);
+        write_formatted(value, writer, formatter, options).await
+    }
+}
+
+async fn write_formatted<F, W>(
+    value: &Value,
+    writer: &mut W,
+    formatter: F,
+    options: &Options,
+) -> Result<(), Error>
+where
+    F: Formatter<Vec<u8>> + Formatter<FmtWriter<String>>,
+    W: AsyncWrite + Unpin,
+{
+    match options.align {
+        Some(alignment) => {
+            let formatter = Aligned::new(formatter, alignment);
+            write_value(value, writer, formatter, options).await
+        }
+        None => write_value(value, writer, formatter, options).await,
+    }
+}
+
+enum Frame<'a> {
+    Array(std::iter::Enumerate<std::slice::Iter<'a, Value>>),
+    Object(serde_json::map::Iter<'a>),
+}
+
+impl<'a> Iterator for Frame<'a> {
+    type Item = (NamespaceKey, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Frame::Array(iter) => iter.next().map(|(i, v)| (NamespaceKey::Array(i), v)),
+            Frame::Object(iter) => iter
+                .next()
+                .map(|(k, v)| (NamespaceKey::Object(k.clone()), v)),
+        }
+    }
+}
+
+/// Walks `value` with an explicit stack so the walk can pause whenever a chunk is written.
+async fn write_value<F, W>(
+    value: &Value,
+    writer: &mut W,
+    formatter: F,
+    options: &Options,
+) -> Result<(), Error>
+where
+    F: Formatter<Vec<u8>>,
+    W: AsyncWrite + Unpin,
+{
+    let mut ser = Serializer::with_options(Vec::with_capacity(CHUNK_SIZE), formatter, options);
+    let mut stack: Vec<Frame> = vec![];
+    let mut next = Some(value);
+
+    loop {
+        match next.take() {
+            Some(container) if ser.should_collapse(container) => {
+                ser.serialize_json_value(container)?;
+                if !stack.is_empty() {
+                    ser.pop_key();
+                }
+            }
+            Some(Value::Array(array)) => {
+                ser.serialize_array_init()?;
+                stack.push(Frame::Array(array.iter().enumerate()));
+            }
+            Some(Value::Object(object)) => {
+                ser.serialize_object_init()?;
+                stack.push(Frame::Object(object.iter()));
+            }
+            Some(scalar) => {
+                ser.serialize_json_value(scalar)?;
+                if !stack.is_empty() {
+                    ser.pop_key();
+                }
+            }
+            None => {}
+        }
+
+        let buf = ser.writer_mut();
+        if buf.len() >= CHUNK_SIZE {
+            writer.write_all(buf).await.map_err(Error::Io)?;
+            buf.clear();
+        }
+
+        let Some(frame) = stack.last_mut() else {
+            break;
+        };
+        match frame.next() {
+            Some((key, child)) => {
+                ser.push_key(key);
+                next = Some(child);
+            }
+            None => {
+                stack.pop();
+                ser.end_container()?;
+                if !stack.is_empty() {
+                    ser.pop_key();
+                }
+            }
+        }
+    }
+
+    ser.end_document()?;
+    writer
+        .write_all(ser.writer_mut())
+        .await
+        .map_err(Error::Io)?;
+    writer.flush().await.map_err(Error::Io)
+}
+
+/// Rebuilds a value from gron statements read line by line from `reader`.
+pub async fn from_async_reader<T, R>(reader: R) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    R: AsyncBufRead + Unpin,
+{
+    let mut lines = reader.lines();
+    let mut value = Value::Null;
+    let mut line = 0;
+    while let Some(text) = lines.next_line().await.map_err(Error::Io)? {
+        for record in text.split('\0') {
+            line += 1;
+            let statement =
+                parse_line(record).map_err(|message| Error::Syntax { line, message })?;
+            apply(&mut value, statement)?;
+        }
+    }
+
+    serde_json::from_value(value).map_err(Error::Deserialize)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn large_value() -> Value {
+        Value::Array(
+            (0..2000)
+                .map(|i| json!({ "id": i, "tags": ["a", "b-c"], "meta": { "ok": true } }))
+                .collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_value_to_async_writer() {
+        let value = large_value();
+        let mut writer = vec![];
+        value_to_async_writer(&value, &mut writer).await.unwrap();
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            crate::to_string(&value).unwrap()
+        );
+
+        let mut writer = vec![];
+        value_to_async_writer(&json!("x"), &mut writer)
+            .await
+            .unwrap();
+        assert_eq!(writer, b"json = \"x\";\n");
+    }
+
+    #[tokio::test]
+    async fn test_to_async_writer() {
+        let value = large_value();
+        let mut writer = vec![];
+        to_async_writer(&value, &mut writer).await.unwrap();
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            crate::to_string(&value).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_to_async_writer_with_options() {
+        let options = Options {
+            leaf_only: true,
+            collapse_depth: Some(3),
+            align: Some(crate::Alignment::Container),
+            ..Options::default()
+        };
+        let value = large_value();
+        let mut writer = vec![];
+        to_async_writer_with_options(&value, &mut writer, &options)
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            crate::to_string_with_options(&value, &options).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_from_async_reader() {
+        let value = large_value();
+        let gron = crate::to_string(&value).unwrap();
+        let parsed: Value = from_async_reader(gron.as_bytes()).await.unwrap();
+        assert_eq!(parsed, value);
+
+        let result: Result<Value, _> = from_async_reader(&b"json = 1;\njson.a 1;\n"[..]).await;
+        assert!(matches!(result, Err(Error::Syntax { line: 2, .. })));
+    }
+}
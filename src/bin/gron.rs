@@ -0,0 +1,278 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::{Parser, ValueEnum};
+use serde_gron::{
+    Alignment, ColorDepth, ColorTheme, FormatType, LineSyntax, LineTerminator, Options, Statement,
+};
+use serde_json::Value;
+
+/// Make JSON greppable.
+#[derive(Debug, Parser)]
+#[command(name = "gron", version, about)]
+struct Args {
+    /// Input file. Reads stdin when omitted or `-`
+    file: Option<PathBuf>,
+
+    /// Root name of the statements
+    #[arg(long, default_value = "json")]
+    root: String,
+
+    /// Force colored output
+    #[arg(short, long, overrides_with = "no_color")]
+    color: bool,
+
+    /// Disable colored output
+    #[arg(short = 'm', long, overrides_with = "color")]
+    no_color: bool,
+
+    /// Color palette of colored output
+    #[arg(long, value_enum, default_value_t = Theme::Dark)]
+    theme: Theme,
+
+    /// Number of colors the terminal supports
+    #[arg(long, value_enum, default_value_t = Depth::Ansi)]
+    color_depth: Depth,
+
+    /// Sort object keys
+    #[arg(long)]
+    sort: bool,
+
+    /// Treat each input line as a separate JSON document
+    #[arg(short, long)]
+    stream: bool,
+
+    /// Print just the values of the statements
+    #[arg(short, long)]
+    values: bool,
+
+    /// Turn gron statements back into JSON
+    #[arg(short, long)]
+    ungron: bool,
+
+    /// Write `=` between keys and values instead of ` = `
+    #[arg(long)]
+    compact: bool,
+
+    /// Omit the `;` at the end of each statement
+    #[arg(long)]
+    no_semicolon: bool,
+
+    /// End statements with CRLF
+    #[arg(long, conflicts_with = "null")]
+    crlf: bool,
+
+    /// End statements with NUL instead of a newline, for `xargs -0`
+    #[arg(short = '0', long)]
+    null: bool,
+
+    /// Omit the `= {};` and `= [];` statements of non-empty objects and arrays
+    #[arg(short, long)]
+    leaf_only: bool,
+
+    /// Write objects and arrays at this depth or deeper as inline JSON
+    #[arg(long, value_name = "DEPTH")]
+    collapse_depth: Option<usize>,
+
+    /// Write objects and arrays with fewer than this many leaves as inline JSON
+    #[arg(long, value_name = "COUNT")]
+    collapse_leaves: Option<usize>,
+
+    /// Align the `=` of consecutive statements in the same object or array
+    #[arg(short, long)]
+    align: bool,
+
+    /// Align the `=` of each block of this many statements
+    #[arg(long, value_name = "LINES", conflicts_with = "align")]
+    align_window: Option<usize>,
+
+    /// Draw the statements as an indented tree
+    #[arg(short, long, conflicts_with_all = ["ungron", "values"])]
+    tree: bool,
+
+    /// Write `path,type,value` records for spreadsheets
+    #[arg(long, conflicts_with_all = ["ungron", "values", "tree", "tsv"])]
+    csv: bool,
+
+    /// Write `path`, `type` and `value` separated by tabs
+    #[arg(long, conflicts_with_all = ["ungron", "values", "tree"])]
+    tsv: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Theme {
+    Dark,
+    Light,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Depth {
+    /// 8 colors
+    #[value(name = "8")]
+    Ansi,
+    /// 256 colors
+    #[value(name = "256")]
+    Ansi256,
+    /// 24-bit colors
+    #[value(name = "24bit")]
+    TrueColor,
+}
+
+impl Args {
+    fn options(&self, format_type: FormatType) -> Options {
+        let syntax = LineSyntax {
+            spaced_delimiter: !self.compact,
+            semicolon: !self.no_semicolon,
+            terminator: if self.null {
+                LineTerminator::Nul
+            } else if self.crlf {
+                LineTerminator::CrLf
+            } else {
+                LineTerminator::Lf
+            },
+        };
+        let theme = match self.theme {
+            Theme::Dark => ColorTheme::dark(),
+            Theme::Light => ColorTheme::light(),
+        };
+        let depth = match self.color_depth {
+            Depth::Ansi => ColorDepth::Ansi,
+            Depth::Ansi256 => ColorDepth::Ansi256,
+            Depth::TrueColor => ColorDepth::TrueColor,
+        };
+        Options {
+            root_name: self.root.clone(),
+            format_type,
+            theme: theme.with_depth(depth),
+            syntax,
+            leaf_only: self.leaf_only,
+            collapse_depth: self.collapse_depth,
+            collapse_leaves: self.collapse_leaves,
+            align: match self.align_window {
+                Some(lines) => Some(Alignment::Window(lines)),
+                None => self.align.then_some(Alignment::Container),
+            },
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        // The reader of the output went away, e.g. `gron big.json | head`.
+        Err(e) if is_broken_pipe(&*e) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("gron: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let mut reader = open_input(args.file.as_ref())?;
+    let stdout = io::stdout();
+    let format_type = if args.color {
+        FormatType::Color
+    } else if args.no_color {
+        FormatType::Regular
+    } else {
+        FormatType::detect(&stdout)
+    };
+    let mut out = BufWriter::new(stdout.lock());
+
+    // Without options that need the whole document, stream the input straight through.
+    if !(args.ungron
+        || args.stream
+        || args.sort
+        || args.values
+        || args.tree
+        || args.csv
+        || args.tsv)
+    {
+        let mut de = serde_json::Deserializer::from_reader(reader);
+        serde_gron::transcode_with_options(&mut de, &mut out, &args.options(format_type))?;
+        de.end()?;
+        return Ok(out.flush()?);
+    }
+
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    if args.ungron {
+        let mut value: Value = serde_gron::from_str(&input)?;
+        if args.sort {
+            sort_keys(&mut value);
+        }
+        serde_json::to_writer_pretty(&mut out, &value)?;
+        writeln!(out)?;
+        return Ok(out.flush()?);
+    }
+
+    let mut value = if args.stream {
+        input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str::<Value>)
+            .collect::<Result<Value, _>>()?
+    } else {
+        serde_json::from_str(&input)?
+    };
+    if args.sort {
+        sort_keys(&mut value);
+    }
+
+    if args.values {
+        for statement in serde_gron::value_to_statements(value) {
+            match statement {
+                Statement::Assign(_, Value::Array(_) | Value::Object(_)) => {}
+                Statement::Assign(_, v) => writeln!(out, "{v}")?,
+                Statement::Delete(_) => {}
+            }
+        }
+    } else if args.tree {
+        serde_gron::to_tree_writer(&value, &mut out, &args.options(format_type))?;
+    } else if args.csv {
+        serde_gron::to_csv_writer(&value, &mut out, &args.options(format_type))?;
+    } else if args.tsv {
+        serde_gron::to_tsv_writer(&value, &mut out, &args.options(format_type))?;
+    } else {
+        serde_gron::value_to_writer_with_options(&value, &mut out, &args.options(format_type))?;
+    }
+
+    Ok(out.flush()?)
+}
+
+fn is_broken_pipe(e: &(dyn Error + 'static)) -> bool {
+    let io = match e.downcast_ref::<serde_gron::Error>() {
+        Some(serde_gron::Error::Io(io)) => io,
+        _ => match e.downcast_ref::<io::Error>() {
+            Some(io) => io,
+            None => return false,
+        },
+    };
+    io.kind() == io::ErrorKind::BrokenPipe
+}
+
+fn open_input(file: Option<&PathBuf>) -> io::Result<Box<dyn BufRead>> {
+    match file {
+        Some(path) if path.as_os_str() != "-" => Ok(Box::new(BufReader::new(File::open(path)?))),
+        _ => Ok(Box::new(io::stdin().lock())),
+    }
+}
+
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Array(array) => array.iter_mut().for_each(sort_keys),
+        Value::Object(object) => {
+            object.sort_keys();
+            object.values_mut().for_each(sort_keys);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,270 @@
+//! Colors for [`FormatType::Color`](crate::FormatType::Color) output.
+
+use alloc::string::String;
+use core::fmt::Write as _;
+
+/// A terminal color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    /// One of the 256 colors of the xterm palette.
+    Fixed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// The approximate RGB values of the eight basic colors, for downsampling.
+const BASIC: [(u8, u8, u8); 8] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+];
+
+impl Color {
+    /// Returns the number of a basic color, or converts the color to one.
+    fn to_basic(self) -> u8 {
+        match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::Fixed(n) if n < 16 => n % 8,
+            Color::Fixed(n) => {
+                let (r, g, b) = fixed_to_rgb(n);
+                Color::Rgb(r, g, b).to_basic()
+            }
+            // Grays are closer to yellow than to white by distance, but look like white.
+            Color::Rgb(r, g, b) if r.max(g).max(b) - r.min(g).min(b) < 32 => {
+                if r.max(g).max(b) < 96 {
+                    0
+                } else {
+                    7
+                }
+            }
+            Color::Rgb(r, g, b) => {
+                let distance = |&(br, bg, bb): &(u8, u8, u8)| {
+                    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2);
+                    d(r, br) + d(g, bg) + d(b, bb)
+                };
+                (0..8)
+                    .min_by_key(|&i| distance(&BASIC[i]))
+                    .unwrap_or_default() as u8
+            }
+        }
+    }
+
+    /// Appends the SGR parameters that select this color as the foreground at `depth`.
+    fn push_sgr(self, out: &mut String, depth: ColorDepth) {
+        match (self, depth) {
+            (Color::Rgb(r, g, b), ColorDepth::TrueColor) => {
+                write!(out, "38;2;{r};{g};{b}").unwrap()
+            }
+            (Color::Rgb(r, g, b), ColorDepth::Ansi256) => {
+                write!(out, "38;5;{}", rgb_to_fixed(r, g, b)).unwrap()
+            }
+            (Color::Fixed(n), ColorDepth::Ansi256 | ColorDepth::TrueColor) => {
+                write!(out, "38;5;{n}").unwrap()
+            }
+            (color, _) => write!(out, "{}", 30 + color.to_basic()).unwrap(),
+        }
+    }
+}
+
+/// Converts a color of the xterm palette above the 16 system colors to RGB.
+fn fixed_to_rgb(n: u8) -> (u8, u8, u8) {
+    if n >= 232 {
+        let level = 8 + 10 * (n - 232);
+        return (level, level, level);
+    }
+    let level = |i: u8| if i == 0 { 0 } else { 55 + 40 * i };
+    let i = n - 16;
+    (level(i / 36), level(i / 6 % 6), level(i % 6))
+}
+
+/// Returns the nearest color of the xterm 6×6×6 color cube.
+fn rgb_to_fixed(r: u8, g: u8, b: u8) -> u8 {
+    let index = |v: u8| match v {
+        0..48 => 0,
+        48..115 => 1,
+        v => (v - 35) / 40,
+    };
+    16 + 36 * index(r) + 6 * index(g) + index(b)
+}
+
+/// How many colors the terminal supports. Colors the depth cannot show are replaced by the
+/// nearest color it can.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    /// The eight basic colors.
+    #[default]
+    Ansi,
+    /// The 256-color xterm palette.
+    Ansi256,
+    /// 24-bit RGB.
+    TrueColor,
+}
+
+/// How one kind of token is written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub const fn new(color: Color) -> Style {
+        Style {
+            color: Some(color),
+            bold: false,
+        }
+    }
+
+    pub const fn bold(self) -> Style {
+        Style { bold: true, ..self }
+    }
+
+    /// Appends `text` to `out`, wrapped in the escape sequences of this style.
+    pub(crate) fn paint(&self, out: &mut String, text: &str, depth: ColorDepth) {
+        if self.color.is_none() && !self.bold {
+            out.push_str(text);
+            return;
+        }
+        out.push_str("\x1b[");
+        if self.bold {
+            out.push('1');
+            if self.color.is_some() {
+                out.push(';');
+            }
+        }
+        if let Some(color) = self.color {
+            color.push_sgr(out, depth);
+        }
+        out.push('m');
+        out.push_str(text);
+        out.push_str("\x1b[0m");
+    }
+}
+
+/// The styles of each kind of token in colored output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorTheme {
+    /// The root name and object keys written with dot notation.
+    pub key: Style,
+    /// Array brackets, and the `[]` and `{}` of empty containers.
+    pub bracket: Style,
+    /// Numbers and array indexes.
+    pub number: Style,
+    /// Strings and quoted object keys.
+    pub string: Style,
+    pub bool: Style,
+    pub null: Style,
+    pub depth: ColorDepth,
+}
+
+impl ColorTheme {
+    /// Basic colors for dark backgrounds: blue keys, magenta brackets, red numbers, yellow
+    /// strings and cyan literals.
+    pub const fn dark() -> ColorTheme {
+        ColorTheme {
+            key: Style::new(Color::Blue),
+            bracket: Style::new(Color::Magenta),
+            number: Style::new(Color::Red),
+            string: Style::new(Color::Yellow),
+            bool: Style::new(Color::Cyan),
+            null: Style::new(Color::Cyan),
+            depth: ColorDepth::Ansi,
+        }
+    }
+
+    /// Darker colors that stay readable on light backgrounds.
+    pub const fn light() -> ColorTheme {
+        ColorTheme {
+            key: Style::new(Color::Rgb(0, 95, 215)),
+            bracket: Style::new(Color::Rgb(135, 0, 135)),
+            number: Style::new(Color::Rgb(175, 0, 0)),
+            string: Style::new(Color::Rgb(0, 135, 0)),
+            bool: Style::new(Color::Rgb(0, 135, 135)),
+            null: Style::new(Color::Rgb(0, 135, 135)),
+            depth: ColorDepth::Ansi,
+        }
+    }
+
+    pub const fn with_depth(self, depth: ColorDepth) -> ColorTheme {
+        ColorTheme { depth, ..self }
+    }
+}
+
+impl Default for ColorTheme {
+    fn default() -> Self {
+        ColorTheme::dark()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paint(style: Style, depth: ColorDepth) -> String {
+        let mut s = String::new();
+        style.paint(&mut s, "x", depth);
+        s
+    }
+
+    #[test]
+    fn test_color_depth() {
+        let style = Style::new(Color::Rgb(0, 95, 215));
+        assert_eq!(
+            paint(style, ColorDepth::TrueColor),
+            "\x1b[38;2;0;95;215mx\x1b[0m"
+        );
+        assert_eq!(paint(style, ColorDepth::Ansi256), "\x1b[38;5;26mx\x1b[0m");
+        assert_eq!(paint(style, ColorDepth::Ansi), "\x1b[34mx\x1b[0m");
+
+        let style = Style::new(Color::Fixed(244)).bold();
+        assert_eq!(
+            paint(style, ColorDepth::Ansi256),
+            "\x1b[1;38;5;244mx\x1b[0m"
+        );
+        assert_eq!(paint(style, ColorDepth::Ansi), "\x1b[1;37mx\x1b[0m");
+
+        assert_eq!(paint(Style::default(), ColorDepth::Ansi), "x");
+        assert_eq!(
+            paint(Style::new(Color::Red), ColorDepth::TrueColor),
+            "\x1b[31mx\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_light_theme_downsamples_to_matching_basic_colors() {
+        let light = ColorTheme::light();
+        let dark = ColorTheme::dark();
+        for (light, basic) in [
+            (light.key, Color::Blue),
+            (light.bracket, Color::Magenta),
+            (light.number, Color::Red),
+            (light.string, Color::Green),
+            (light.bool, dark.bool.color.unwrap()),
+        ] {
+            assert_eq!(
+                light.color.unwrap().to_basic(),
+                basic.to_basic(),
+                "{light:?}"
+            );
+        }
+    }
+}
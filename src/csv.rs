@@ -0,0 +1,377 @@
+//! `path,type,value` rows for spreadsheets, e.g. `json.a[0],number,1`.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{Display, Write as _},
+    mem,
+};
+
+use crate::{
+    apply::from_pairs,
+    de::parse_path,
+    languages::serialize,
+    ser::{is_identifier, Formatter, NamespaceKey},
+    write::{write_json_string, FmtWriter},
+    Error, Options, Write,
+};
+
+const HEADER: [&str; 3] = ["path", "type", "value"];
+
+/// Writes a header and one `path,type,value` record per leaf.
+///
+/// The type is the name of the JSON type. Strings are written without JSON quotes, `null` as
+/// an empty value, and empty or collapsed containers as compact JSON. Fields are quoted as in
+/// RFC 4180 and records end with CRLF.
+#[derive(Debug)]
+struct CsvFormatter {
+    separator: char,
+}
+
+impl CsvFormatter {
+    fn write_field<W: Write>(&self, writer: &mut W, field: &str) -> Result<(), Error> {
+        if field.contains([self.separator, '"', '\r', '\n']) {
+            writer.write_str("\"")?;
+            writer.write_str(&field.replace('"', "\"\""))?;
+            writer.write_str("\"")
+        } else {
+            writer.write_str(field)
+        }
+    }
+
+    fn write_typed<W: Write>(&self, writer: &mut W, ty: &str, value: &str) -> Result<(), Error> {
+        writer.write_str(ty)?;
+        write!(writer, "{}", self.separator)?;
+        self.write_field(writer, value)
+    }
+}
+
+impl<W: Write> Formatter<W> for CsvFormatter {
+    fn write_root(&mut self, key: &mut String, ns_root: &str) {
+        key.push_str(ns_root);
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        match ns {
+            NamespaceKey::Array(n) => write!(key, "[{n}]").unwrap(),
+            NamespaceKey::Object(k) if is_identifier(k) => write!(key, ".{k}").unwrap(),
+            NamespaceKey::Object(k) => {
+                key.push('[');
+                write_json_string(k, |s| key.write_str(s)).unwrap();
+                key.push(']');
+            }
+        }
+    }
+
+    fn begin_document(&mut self, writer: &mut W) -> Result<(), Error> {
+        let [path, ty, value] = HEADER;
+        let separator = self.separator;
+        write!(writer, "{path}{separator}{ty}{separator}{value}\r\n")
+    }
+
+    fn write_key(&mut self, writer: &mut W, key: &str) -> Result<(), Error> {
+        self.write_field(writer, key)
+    }
+
+    fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+        write!(writer, "{}", self.separator)
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("\r\n")
+    }
+
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.write_typed(writer, "null", "")
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        self.write_typed(writer, "boolean", &value.to_string())
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        self.write_typed(writer, "number", &value.to_string())
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        self.write_typed(writer, "string", value)
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.write_typed(writer, "array", "[]")
+    }
+
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.write_typed(writer, "object", "{}")
+    }
+
+    fn write_json(&mut self, writer: &mut W, value: &Value) -> Result<(), Error> {
+        let ty = if value.is_array() { "array" } else { "object" };
+        self.write_typed(writer, ty, &value.to_string())
+    }
+}
+
+pub fn to_csv_string(value: &impl Serialize, options: &Options) -> Result<String, Error> {
+    let mut s = String::new();
+    to_csv_writer(value, FmtWriter(&mut s), options)?;
+    Ok(s)
+}
+
+/// Writes `value` as comma-separated `path,type,value` records, one per leaf, after a header.
+///
+/// Paths are gron keys, so they can be filtered and sorted as text. Read the records back
+/// with [`from_csv_str`]. The format type, line syntax and leaf-only options are ignored.
+///
+/// ```
+/// use serde_gron::Options;
+/// use serde_json::json;
+///
+/// let value = json!({ "a": [null, "x,y"] });
+/// assert_eq!(
+///     serde_gron::to_csv_string(&value, &Options::default()).unwrap(),
+///     "path,type,value\r\njson.a[0],null,\r\njson.a[1],string,\"x,y\"\r\n"
+/// );
+/// ```
+pub fn to_csv_writer(
+    value: &impl Serialize,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    write_records(value, writer, ',', options)
+}
+
+pub fn to_tsv_string(value: &impl Serialize, options: &Options) -> Result<String, Error> {
+    let mut s = String::new();
+    to_tsv_writer(value, FmtWriter(&mut s), options)?;
+    Ok(s)
+}
+
+/// Writes `value` like [`to_csv_writer`], but with fields separated by tabs.
+pub fn to_tsv_writer(
+    value: &impl Serialize,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    write_records(value, writer, '\t', options)
+}
+
+fn write_records(
+    value: &impl Serialize,
+    writer: impl Write,
+    separator: char,
+    options: &Options,
+) -> Result<(), Error> {
+    let options = Options {
+        leaf_only: true,
+        ..options.clone()
+    };
+    serialize(value, writer, CsvFormatter { separator }, &options)
+}
+
+/// Deserializes a `T` from the records written by [`to_csv_string`].
+///
+/// The header is optional, records may end with CRLF or LF, and the rows may be in any
+/// order. Syntax errors report the line on which the record starts.
+///
+/// ```
+/// use serde_json::{json, Value};
+///
+/// let csv = "path,type,value\njson.a[1],boolean,true\njson.b,string,\"say \"\"hi\"\"\"\n";
+/// let value: Value = serde_gron::from_csv_str(csv).unwrap();
+/// assert_eq!(value, json!({ "a": [null, true], "b": "say \"hi\"" }));
+/// ```
+pub fn from_csv_str<T: DeserializeOwned>(s: &str) -> Result<T, Error> {
+    read_records(s, ',')
+}
+
+/// Deserializes a `T` from the records written by [`to_tsv_string`].
+pub fn from_tsv_str<T: DeserializeOwned>(s: &str) -> Result<T, Error> {
+    read_records(s, '\t')
+}
+
+fn read_records<T: DeserializeOwned>(s: &str, separator: char) -> Result<T, Error> {
+    let mut pairs = Vec::new();
+    for (i, (line, record)) in parse_records(s, separator)?.into_iter().enumerate() {
+        if i == 0 && record == HEADER {
+            continue;
+        }
+        let syntax = |message| Error::Syntax { line, message };
+        let [path, ty, value] = <[String; 3]>::try_from(record)
+            .map_err(|record| syntax(format!("Expected 3 fields, found {}", record.len())))?;
+        let path = parse_path(&path).map_err(syntax)?;
+        pairs.push((path, parse_value(&ty, value).map_err(syntax)?));
+    }
+
+    from_pairs(pairs)
+}
+
+fn parse_value(ty: &str, value: String) -> Result<Value, String> {
+    let parsed = match ty {
+        "null" => value.is_empty().then_some(Value::Null),
+        "boolean" => value.parse().ok().map(Value::Bool),
+        "string" => return Ok(Value::String(value)),
+        "number" | "array" | "object" => serde_json::from_str(&value).ok(),
+        _ => return Err(format!("Unknown type `{ty}`")),
+    };
+    parsed
+        .filter(|v| match ty {
+            "number" => v.is_number(),
+            "array" => v.is_array(),
+            "object" => v.is_object(),
+            _ => true,
+        })
+        .ok_or_else(|| format!("Invalid {ty} `{value}`"))
+}
+
+/// Splits RFC 4180 text into records, each with the line it starts on. Blank lines are
+/// skipped.
+fn parse_records(s: &str, separator: char) -> Result<Vec<(usize, Vec<String>)>, Error> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    // Whether the current field was quoted, after which only a separator or newline may follow.
+    let mut quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                        None => {
+                            return Err(Error::Syntax {
+                                line: start,
+                                message: "Unterminated quoted field".to_string(),
+                            })
+                        }
+                    }
+                }
+            }
+            c if c == separator => {
+                record.push(mem::take(&mut field));
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if !(record.is_empty() && field.is_empty() && !quoted) {
+                    record.push(mem::take(&mut field));
+                    records.push((start, mem::take(&mut record)));
+                }
+                quoted = false;
+                line += 1;
+                start = line;
+            }
+            _ if quoted => {
+                return Err(Error::Syntax {
+                    line,
+                    message: "Expected a separator after a quoted field".to_string(),
+                })
+            }
+            c => field.push(c),
+        }
+    }
+    if !(record.is_empty() && field.is_empty() && !quoted) {
+        record.push(field);
+        records.push((start, record));
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn value() -> Value {
+        json!({
+            "a": [1.5, { "b c": "d\n\"e\"" }, []],
+            "f": { "g": false, "h": null, "i": "" }
+        })
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let csv = to_csv_string(&value(), &Options::default()).unwrap();
+        let expected = [
+            "path,type,value",
+            "json.a[0],number,1.5",
+            r#""json.a[1][""b c""]",string,"d
+""e""""#,
+            "json.a[2],array,[]",
+            "json.f.g,boolean,false",
+            "json.f.h,null,",
+            "json.f.i,string,",
+            "",
+        ];
+        assert_eq!(csv, expected.join("\r\n"));
+        assert_eq!(from_csv_str::<Value>(&csv).unwrap(), value());
+    }
+
+    #[test]
+    fn test_tsv_round_trip() {
+        let options = Options {
+            collapse_depth: Some(2),
+            ..Options::default()
+        };
+        let tsv = to_tsv_string(&value(), &options).unwrap();
+        let expected = [
+            "path\ttype\tvalue",
+            "json.a[0]\tnumber\t1.5",
+            r#"json.a[1]	object	"{""b c"":""d\n\""e\""""}""#,
+            "json.a[2]\tarray\t[]",
+            "json.f.g\tboolean\tfalse",
+            "json.f.h\tnull\t",
+            "json.f.i\tstring\t",
+            "",
+        ];
+        assert_eq!(tsv, expected.join("\r\n"));
+        assert_eq!(from_tsv_str::<Value>(&tsv).unwrap(), value());
+    }
+
+    #[test]
+    fn test_from_csv_str_errors() {
+        let error = |s| from_csv_str::<Value>(s).unwrap_err().to_string();
+        assert_eq!(
+            error("json.a,number,x"),
+            "Syntax error at line 1: Invalid number `x`"
+        );
+        assert_eq!(
+            error("json.a,string,\"a\nb\"\njson.b,date,1"),
+            "Syntax error at line 3: Unknown type `date`"
+        );
+        assert_eq!(
+            error("\njson.a,string"),
+            "Syntax error at line 2: Expected 3 fields, found 2"
+        );
+        assert_eq!(
+            error("json.a,string,\"x"),
+            "Syntax error at line 1: Unterminated quoted field"
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Display};
+
+use crate::{apply::apply, ser::NamespaceKey, Error, Path};
+
+/// A single gron statement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    /// `json.a.b = 1;`
+    Assign(Path, Value),
+    /// `delete json.a.b;`
+    Delete(Path),
+}
+
+impl Statement {
+    pub fn path(&self) -> &Path {
+        match self {
+            Statement::Assign(path, _) => path,
+            Statement::Delete(path) => path,
+        }
+    }
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Assign(path, value) => write!(f, "{path} = {value};"),
+            Statement::Delete(path) => write!(f, "delete {path};"),
+        }
+    }
+}
+
+/// Rebuilds a value from gron text ("ungron").
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, Error> {
+    let mut value = Value::Null;
+    apply(&mut value, parse_str(s)?)?;
+    serde_json::from_value(value).map_err(Error::Deserialize)
+}
+
+/// Parses gron text into statements. Blank lines are skipped.
+///
+/// Statements may be terminated by `\n`, `\r\n` or `\0`, and the trailing `;` and the
+/// spaces around `=` are optional. Syntax errors report the position of the statement as
+/// its line number.
+pub fn parse_str(s: &str) -> Result<Vec<Statement>, Error> {
+    s.split(['\n', '\0'])
+        .enumerate()
+        .filter_map(|(i, line)| {
+            parse_line(line)
+                .map_err(|message| Error::Syntax {
+                    line: i + 1,
+                    message,
+                })
+                .transpose()
+        })
+        .collect()
+}
+
+/// Parses a single gron statement such as `json.a[0] = "x";`.
+pub fn parse_statement(s: &str) -> Result<Statement, Error> {
+    let syntax = |message| Error::Syntax { line: 1, message };
+    parse_line(s)
+        .map_err(syntax)?
+        .ok_or_else(|| syntax("Empty statement".to_string()))
+}
+
+pub(crate) fn parse_line(line: &str) -> Result<Option<Statement>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let mut parser = Parser::new(line);
+    if let Some(rest) = line.strip_prefix("delete") {
+        let target = rest.trim_start();
+        if rest.len() != target.len() && !target.starts_with('=') {
+            parser.pos = line.len() - target.len();
+            let path = parser.path()?;
+            parser.skip_ws();
+            parser.eat(';');
+            parser.skip_ws();
+            parser.expect_end()?;
+            return Ok(Some(Statement::Delete(path)));
+        }
+    }
+
+    let path = parser.path()?;
+    parser.skip_ws();
+    if !parser.eat('=') {
+        return Err(format!("Expected `=` at column {}", parser.pos + 1));
+    }
+    let value = parser.value()?;
+
+    Ok(Some(Statement::Assign(path, value)))
+}
+
+/// Parses the key of a statement, such as `json.a[0]["b c"]`.
+pub(crate) fn parse_path(s: &str) -> Result<Path, String> {
+    let mut parser = Parser::new(s.trim());
+    let path = parser.path()?;
+    parser.expect_end()?;
+    Ok(path)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Parser<'a> {
+        Parser { input, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect_end(&self) -> Result<(), String> {
+        if self.pos == self.input.len() {
+            Ok(())
+        } else {
+            Err(format!("Unexpected input at column {}", self.pos + 1))
+        }
+    }
+
+    fn ident(&mut self) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| {
+                let valid = c.is_ascii_alphabetic() || c == '_' || c == '$';
+                !(valid || (i > 0 && c.is_ascii_digit()))
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        if len == 0 {
+            return Err(format!("Expected identifier at column {}", self.pos + 1));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    fn path(&mut self) -> Result<Path, String> {
+        let mut path = Path::new(self.ident()?);
+        loop {
+            if self.eat('.') {
+                path.push(NamespaceKey::Object(self.ident()?.to_string()));
+            } else if self.eat('[') {
+                self.skip_ws();
+                let key = if self.peek() == Some('"') {
+                    NamespaceKey::Object(self.string()?)
+                } else {
+                    NamespaceKey::Array(self.index()?)
+                };
+                self.skip_ws();
+                if !self.eat(']') {
+                    return Err(format!("Expected `]` at column {}", self.pos + 1));
+                }
+                path.push(key);
+            } else {
+                return Ok(path);
+            }
+        }
+    }
+
+    fn index(&mut self) -> Result<usize, String> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let n = rest[..len]
+            .parse()
+            .map_err(|_| format!("Expected array index at column {}", self.pos + 1))?;
+        self.pos += len;
+        Ok(n)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let mut stream = serde_json::Deserializer::from_str(self.rest()).into_iter::<String>();
+        match stream.next() {
+            Some(Ok(s)) => {
+                self.pos += stream.byte_offset();
+                Ok(s)
+            }
+            _ => Err(format!("Invalid string key at column {}", self.pos + 1)),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        let rest = self.rest().trim();
+        let parsed = match rest.strip_suffix(';') {
+            Some(value) => serde_json::from_str(value).or_else(|_| serde_json::from_str(rest)),
+            None => serde_json::from_str(rest),
+        };
+        parsed.map_err(|e| format!("Invalid value: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_str() {
+        let statements = parse_str(
+            "json = {};
+json.a = [];
+json.a[0] = \"x;\";
+
+json[\"b-c\"] = null;
+delete json.d;
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Assign(Path::new("json"), json!({})),
+                Statement::Assign(
+                    Path::with_keys("json", vec![NamespaceKey::Object("a".into())]),
+                    json!([])
+                ),
+                Statement::Assign(
+                    Path::with_keys(
+                        "json",
+                        vec![NamespaceKey::Object("a".into()), NamespaceKey::Array(0)]
+                    ),
+                    json!("x;")
+                ),
+                Statement::Assign(
+                    Path::with_keys("json", vec![NamespaceKey::Object("b-c".into())]),
+                    json!(null)
+                ),
+                Statement::Delete(Path::with_keys(
+                    "json",
+                    vec![NamespaceKey::Object("d".into())]
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_line_syntax() {
+        let expected = parse_str("json = [];\njson[0] = \"a;b\";\n").unwrap();
+        for input in [
+            "json=[];\r\njson[0]=\"a;b\";\r\n",
+            "json = []\njson[0] = \"a;b\"",
+            "json=[]\0json[0]=\"a;b\"\0",
+        ] {
+            assert_eq!(parse_str(input).unwrap(), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(matches!(
+            parse_str("json = 1;\njson.a 1;"),
+            Err(Error::Syntax { line: 2, .. })
+        ));
+        assert!(parse_statement("json[x] = 1;").is_err());
+        assert!(parse_statement("json.a = ;").is_err());
+    }
+
+    #[test]
+    fn test_from_str() {
+        let value = json!({ "a": [1, true, null], "b": { "c-d": "e\"f" } });
+        let s = crate::to_string(&value).unwrap();
+        assert_eq!(from_str::<Value>(&s).unwrap(), value);
+    }
+
+    #[test]
+    fn test_display() {
+        for line in [
+            "json = {};",
+            "json.a[0][\"b-c\"] = \"x\";",
+            "delete json.a[1];",
+        ] {
+            assert_eq!(parse_statement(line).unwrap().to_string(), line);
+        }
+    }
+}
@@ -0,0 +1,505 @@
+//! Flattening to and from environment variables, e.g. `APP__DATABASE__HOSTS__0=db1`.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use crate::{
+    apply::unflatten,
+    ser::{Formatter, NamespaceKey, Serializer},
+    string_value::StringValue,
+    write::FmtWriter,
+    Error, Options, Path, Write,
+};
+
+/// Settings for the environment variable format.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnvOptions {
+    /// The first segment of every variable name, e.g. `APP`. May be empty.
+    pub prefix: String,
+    /// Written between segments. Defaults to `__`.
+    pub separator: String,
+    pub case: EnvCase,
+}
+
+impl EnvOptions {
+    pub fn new(prefix: impl Into<String>) -> EnvOptions {
+        EnvOptions {
+            prefix: prefix.into(),
+            separator: "__".to_string(),
+            case: EnvCase::Upper,
+        }
+    }
+}
+
+impl Default for EnvOptions {
+    fn default() -> Self {
+        EnvOptions::new("")
+    }
+}
+
+/// The case object keys and the prefix are converted to in variable names.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnvCase {
+    /// `APP__DATABASE`. Names are lowercased again when read back.
+    #[default]
+    Upper,
+    /// `app__database`
+    Lower,
+    /// Keys are used as they are.
+    Preserve,
+}
+
+impl EnvCase {
+    fn apply(self, s: &str) -> String {
+        match self {
+            EnvCase::Upper => s.to_uppercase(),
+            EnvCase::Lower => s.to_lowercase(),
+            EnvCase::Preserve => s.to_string(),
+        }
+    }
+
+    fn revert(self, s: &str) -> String {
+        match self {
+            EnvCase::Upper => s.to_lowercase(),
+            EnvCase::Lower | EnvCase::Preserve => s.to_string(),
+        }
+    }
+}
+
+/// Writes one `NAME=value` line per leaf. `null` is written as an empty value and empty
+/// containers as `[]` and `{}`.
+///
+/// Values made of anything but letters, digits and `_-.,:/@%+=` are written in double quotes
+/// with dotenv escapes, e.g. `"a \"b\"\n"`. Names may only contain ASCII letters, digits and
+/// `_`, must not start with a digit, and no key may contain the separator; other keys are an
+/// error, since they could not be read back.
+#[derive(Debug, Default)]
+pub struct EnvFormatter {
+    separator: String,
+    case: EnvCase,
+    /// A key that contains the separator, reported by the next `write_key`, which is always
+    /// for a statement below it.
+    invalid: Option<String>,
+}
+
+impl<W: Write> Formatter<W> for EnvFormatter {
+    fn write_root(&mut self, key: &mut String, ns_root: &str) {
+        key.push_str(&self.case.apply(ns_root));
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        if !key.is_empty() {
+            key.push_str(&self.separator);
+        }
+        match ns {
+            NamespaceKey::Array(n) => key.push_str(&n.to_string()),
+            NamespaceKey::Object(k) => {
+                let segment = self.case.apply(k);
+                if !self.separator.is_empty() && segment.contains(&*self.separator) {
+                    self.invalid = Some(k.clone());
+                }
+                key.push_str(&segment);
+            }
+        }
+    }
+
+    fn write_key(&mut self, writer: &mut W, key: &str) -> Result<(), Error> {
+        if let Some(k) = &self.invalid {
+            return Err(Error::Custom(format!(
+                "Key `{k}` contains the separator `{}`",
+                self.separator
+            )));
+        }
+        if !is_name(key) {
+            return Err(Error::Custom(format!(
+                "`{key}` is not a valid environment variable name"
+            )));
+        }
+        writer.write_str(key)
+    }
+
+    fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("=")
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("\n")
+    }
+
+    fn write_null(&mut self, _writer: &mut W) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        if value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.,:/@%+=".contains(c))
+        {
+            return writer.write_str(value);
+        }
+        let mut quoted = String::with_capacity(value.len() + 2);
+        quoted.push('"');
+        for c in value.chars() {
+            match c {
+                '\\' => quoted.push_str("\\\\"),
+                '"' => quoted.push_str("\\\""),
+                '\n' => quoted.push_str("\\n"),
+                '\r' => quoted.push_str("\\r"),
+                '\t' => quoted.push_str("\\t"),
+                '$' => quoted.push_str("\\$"),
+                '`' => quoted.push_str("\\`"),
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        writer.write_str(&quoted)
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("[]")
+    }
+
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("{}")
+    }
+}
+
+/// Returns whether `name` is a portable environment variable name.
+fn is_name(name: &str) -> bool {
+    let mut bytes = name.bytes();
+    matches!(bytes.next(), Some(b) if b.is_ascii_alphabetic() || b == b'_')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+pub fn to_env_string(value: &impl Serialize, options: &EnvOptions) -> Result<String, Error> {
+    let mut s = String::new();
+    to_env_writer(value, FmtWriter(&mut s), options)?;
+    Ok(s)
+}
+
+/// Writes `value` as environment variable assignments.
+///
+/// ```
+/// use serde_gron::EnvOptions;
+/// use serde_json::json;
+///
+/// let value = json!({ "database": { "hosts": ["db1", "db2"], "port": 5432 } });
+/// assert_eq!(
+///     serde_gron::to_env_string(&value, &EnvOptions::new("app")).unwrap(),
+///     "APP__DATABASE__HOSTS__0=db1\nAPP__DATABASE__HOSTS__1=db2\nAPP__DATABASE__PORT=5432\n"
+/// );
+/// ```
+pub fn to_env_writer(
+    value: &impl Serialize,
+    writer: impl Write,
+    options: &EnvOptions,
+) -> Result<(), Error> {
+    let formatter = EnvFormatter {
+        separator: options.separator.clone(),
+        case: options.case,
+        invalid: None,
+    };
+    let options = Options {
+        root_name: options.prefix.clone(),
+        leaf_only: true,
+        ..Options::default()
+    };
+    let mut ser = Serializer::with_options(writer, formatter, &options);
+    value.serialize(&mut ser)?;
+    ser.end_document()
+}
+
+/// Deserializes a `T` from the environment variables of the current process that start with
+/// `prefix`, using the default [`EnvOptions`].
+///
+/// Variables whose name or value is not valid Unicode are skipped.
+#[cfg(feature = "std")]
+pub fn from_env<T: DeserializeOwned>(prefix: &str) -> Result<T, Error> {
+    let vars = std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)));
+    from_env_vars(vars, &EnvOptions::new(prefix))
+}
+
+/// Deserializes a `T` from the `NAME=value` lines written by [`to_env_string`], as in a
+/// `.env` file.
+///
+/// Blank lines, `#` comments and an `export ` before the name are skipped. Values in double
+/// quotes are unescaped, values in single quotes are taken literally, and other values are
+/// trimmed. The variables are then read as by [`from_env_vars`].
+///
+/// ```
+/// use serde_gron::EnvOptions;
+/// use serde_json::{json, Value};
+///
+/// let env = "# app\nAPP__NAME=\"say \\\"hi\\\"\\n\"\nexport APP__PORT=8080\n";
+/// let value: Value = serde_gron::from_env_str(env, &EnvOptions::new("app")).unwrap();
+/// assert_eq!(value, json!({ "name": "say \"hi\"\n", "port": "8080" }));
+/// ```
+pub fn from_env_str<T: DeserializeOwned>(s: &str, options: &EnvOptions) -> Result<T, Error> {
+    let mut vars = Vec::new();
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let syntax = |message| Error::Syntax {
+            line: i + 1,
+            message,
+        };
+        let line = line.strip_prefix("export ").map_or(line, str::trim_start);
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| syntax("Expected `=`".to_string()))?;
+        vars.push((
+            name.trim_end(),
+            unquote(value.trim_start()).map_err(syntax)?,
+        ));
+    }
+
+    from_env_vars(vars, options)
+}
+
+fn unquote(value: &str) -> Result<String, String> {
+    if let Some(rest) = value.strip_prefix('\'') {
+        return match rest.split_once('\'') {
+            Some((literal, after)) if after.trim().is_empty() => Ok(literal.to_string()),
+            Some(_) => Err("Unexpected input after the closing quote".to_string()),
+            None => Err("Unterminated single-quoted value".to_string()),
+        };
+    }
+    let Some(rest) = value.strip_prefix('"') else {
+        return Ok(value.trim_end().to_string());
+    };
+
+    let mut out = String::with_capacity(rest.len());
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if chars.as_str().trim().is_empty() => return Ok(out),
+            '"' => return Err("Unexpected input after the closing quote".to_string()),
+            '\\' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some('t') => out.push('\t'),
+                Some(c @ ('\\' | '"' | '$' | '`')) => out.push(c),
+                Some(c) => return Err(format!("Invalid escape `\\{c}`")),
+                None => break,
+            },
+            c => out.push(c),
+        }
+    }
+    Err("Unterminated double-quoted value".to_string())
+}
+
+/// Deserializes a `T` from name/value pairs, ignoring names that do not start with the
+/// prefix.
+///
+/// Names have no array syntax, so every segment is an object key, as in
+/// [`from_properties_str`](crate::from_properties_str): `APP__PORTS__8080` is the key `8080`.
+/// Sequence fields accept objects keyed by index, so `APP__HOSTS__0` still fills a
+/// `Vec`, with `null` for indexes that are missing. Values are parsed into the type each field
+/// expects, so `PORT=8080` can fill both a `u16` and a `String`.
+///
+/// ```
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     hosts: Vec<String>,
+///     port: u16,
+/// }
+///
+/// let vars = [("APP__PORT", "8080"), ("APP__HOSTS__0", "db1"), ("HOME", "/root")];
+/// let config: Config =
+///     serde_gron::from_env_vars(vars, &serde_gron::EnvOptions::new("app")).unwrap();
+/// assert_eq!(config.hosts, ["db1"]);
+/// assert_eq!(config.port, 8080);
+/// ```
+pub fn from_env_vars<T, I, K, V>(vars: I, options: &EnvOptions) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: Into<String>,
+{
+    let mut prefix = options.case.apply(&options.prefix);
+    if !prefix.is_empty() {
+        prefix.push_str(&options.separator);
+    }
+
+    let mut pairs = Vec::new();
+    for (name, value) in vars {
+        let Some(rest) = name.as_ref().strip_prefix(&*prefix) else {
+            continue;
+        };
+        let keys = rest
+            .split(&*options.separator)
+            .map(|segment| NamespaceKey::Object(options.case.revert(segment)))
+            .collect();
+        pairs.push((Path::with_keys("", keys), Value::String(value.into())));
+    }
+
+    T::deserialize(StringValue(&unflatten(pairs)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{collections::BTreeMap, vec};
+
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Config {
+        name: String,
+        debug: bool,
+        database: Database,
+        limits: BTreeMap<String, u32>,
+        mode: Mode,
+        empty: Vec<u8>,
+    }
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Database {
+        hosts: Vec<String>,
+        port: u16,
+        password: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Mode {
+        Fast,
+    }
+
+    #[test]
+    fn test_env_round_trip() {
+        let config = Config {
+            name: "8080".into(),
+            debug: true,
+            database: Database {
+                hosts: vec!["db1".into(), "db2".into()],
+                port: 5432,
+                password: None,
+            },
+            limits: [("0".to_string(), 1)].into(),
+            mode: Mode::Fast,
+            empty: vec![],
+        };
+        let options = EnvOptions::new("app");
+        let env = to_env_string(&config, &options).unwrap();
+        assert_eq!(
+            env,
+            "APP__NAME=8080
+APP__DEBUG=true
+APP__DATABASE__HOSTS__0=db1
+APP__DATABASE__HOSTS__1=db2
+APP__DATABASE__PORT=5432
+APP__DATABASE__PASSWORD=
+APP__LIMITS__0=1
+APP__MODE=fast
+APP__EMPTY=[]
+"
+        );
+
+        let parsed: Config = from_env_str(&env, &options).unwrap();
+        assert_eq!(parsed, config);
+    }
+
+    #[test]
+    fn test_env_quoting() {
+        let value = json!({ "a": "x y\n\"$HOME\" `id` \\", "b": "a-b.c:1/2", "c": "" });
+        let env = to_env_string(&value, &EnvOptions::default()).unwrap();
+        assert_eq!(
+            env,
+            "A=\"x y\\n\\\"\\$HOME\\\" \\`id\\` \\\\\"\nB=a-b.c:1/2\nC=\n"
+        );
+        let parsed: Value = from_env_str(&env, &EnvOptions::default()).unwrap();
+        assert_eq!(parsed, value);
+
+        let parsed: Value = from_env_str("A='x \\n'\n", &EnvOptions::default()).unwrap();
+        assert_eq!(parsed, json!({ "a": "x \\n" }));
+        assert!(matches!(
+            from_env_str::<Value>("A=1\nB=\"x", &EnvOptions::default()),
+            Err(Error::Syntax { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_env_invalid_names() {
+        let error = |value: Value| to_env_string(&value, &EnvOptions::default()).unwrap_err();
+        assert!(matches!(error(json!({ "a-b": 1 })), Error::Custom(m) if m.contains("`A-B`")));
+        assert!(matches!(error(json!({ "a__b": 1 })), Error::Custom(m) if m.contains("`a__b`")));
+        assert!(matches!(error(json!([1])), Error::Custom(_)));
+        assert!(matches!(error(json!(1)), Error::Custom(_)));
+        assert!(matches!(
+            error(json!({ "a": { "b__c": {} } })),
+            Error::Custom(m) if m.contains("`b__c`")
+        ));
+        let value = json!({ "a": { "b": 1 }, "d_e": 2 });
+        assert_eq!(
+            to_env_string(&value, &EnvOptions::default()).unwrap(),
+            "A__B=1\nD_E=2\n"
+        );
+    }
+
+    #[test]
+    fn test_env_options() {
+        let options = EnvOptions {
+            prefix: String::new(),
+            separator: "_".into(),
+            case: EnvCase::Preserve,
+        };
+        let value = json!({ "a": { "B": [null, 1.5] } });
+        let env = to_env_string(&value, &options).unwrap();
+        assert_eq!(env, "a_B_0=\na_B_1=1.5\n");
+
+        let parsed: Value = from_env_str(&env, &options).unwrap();
+        assert_eq!(parsed, json!({ "a": { "B": { "0": "", "1": "1.5" } } }));
+
+        let result: Result<Database, _> =
+            from_env_vars([("PORT", "x"), ("HOSTS__0", "a")], &EnvOptions::default());
+        assert!(matches!(result, Err(Error::Custom(m)) if m.contains("invalid value `x`")));
+    }
+
+    #[test]
+    fn test_env_numeric_segments() {
+        let options = EnvOptions::new("app");
+        let value: Value = from_env_vars([("APP__PORTS__8080", "x")], &options).unwrap();
+        assert_eq!(value, json!({ "ports": { "8080": "x" } }));
+        let ports: BTreeMap<String, BTreeMap<String, String>> =
+            from_env_vars([("APP__PORTS__8080", "x")], &options).unwrap();
+        assert_eq!(ports["ports"]["8080"], "x");
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Hosts {
+            hosts: Vec<Option<String>>,
+        }
+        let vars = [("APP__HOSTS__2", "c"), ("APP__HOSTS__0", "a")];
+        let hosts: Hosts = from_env_vars(vars, &options).unwrap();
+        assert_eq!(hosts.hosts, [Some("a".into()), None, Some("c".into())]);
+        let result: Result<Hosts, _> = from_env_vars([("APP__HOSTS__8080", "x")], &options);
+        assert!(matches!(result, Err(Error::Custom(m)) if m.contains("8080")));
+        let result: Result<Hosts, _> = from_env_vars([("APP__HOSTS__01", "x")], &options);
+        assert!(result.is_err());
+    }
+}
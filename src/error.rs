@@ -0,0 +1,76 @@
+use alloc::string::{String, ToString};
+use serde::{de, ser};
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::Path;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid root name")]
+    InvalidRootName,
+
+    #[error("Reached end of file")]
+    Eof,
+    #[error(transparent)]
+    Serialize(serde_json::Error),
+    #[error(transparent)]
+    Deserialize(serde_json::Error),
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    Io(io::Error),
+    #[error("An error occurred while formatting")]
+    Fmt,
+
+    #[error("Map key must be a string")]
+    KeyMustBeAString,
+
+    #[error("Syntax error at line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error("Cannot assign to `{0}`: parent is not a container of the expected type")]
+    PathConflict(Path),
+    #[error("Cannot assign to `{0}`: too many array elements would be filled with `null`")]
+    SparseArray(Path),
+
+    #[cfg(feature = "yaml")]
+    #[error(transparent)]
+    Yaml(serde_yaml::Error),
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    Toml(toml::ser::Error),
+    #[cfg(feature = "cbor")]
+    #[error(transparent)]
+    Cbor(ciborium::ser::Error<io::Error>),
+    #[cfg(feature = "cbor")]
+    #[error(transparent)]
+    CborDecode(ciborium::de::Error<io::Error>),
+    #[cfg(feature = "msgpack")]
+    #[error(transparent)]
+    MessagePack(rmp_serde::encode::Error),
+
+    #[error("Invalid JSON Patch: {0}")]
+    Patch(String),
+    #[error("Invalid path `{path}`: {message}")]
+    InvalidPath { path: String, message: String },
+
+    #[error("Error: {0}")]
+    Custom(String),
+}
+
+impl ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: core::fmt::Display,
+    {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: core::fmt::Display,
+    {
+        Self::Custom(msg.to_string())
+    }
+}
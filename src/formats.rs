@@ -0,0 +1,155 @@
+//! Conversions between gron and other serde data formats.
+//!
+//! The `*_to_gron` functions stream from the format's deserializer straight into the gron
+//! serializer. The `gron_to_*` functions rebuild the document with [`from_str`] first, since
+//! gron statements may arrive in any order.
+
+use serde_json::Value;
+
+use crate::{from_str, Error};
+
+#[cfg(any(
+    feature = "yaml",
+    feature = "toml",
+    feature = "cbor",
+    feature = "msgpack"
+))]
+fn transcode_to_string<'de>(deserializer: impl serde::Deserializer<'de>) -> Result<String, Error> {
+    let mut writer = vec![];
+    crate::transcode(deserializer, &mut writer)?;
+    Ok(String::from_utf8(writer).unwrap())
+}
+
+/// Converts a YAML document to gron.
+#[cfg(feature = "yaml")]
+pub fn yaml_to_gron(input: &str) -> Result<String, Error> {
+    transcode_to_string(serde_yaml::Deserializer::from_str(input))
+}
+
+/// Converts gron statements to a YAML document.
+#[cfg(feature = "yaml")]
+pub fn gron_to_yaml(input: &str) -> Result<String, Error> {
+    serde_yaml::to_string(&from_str::<Value>(input)?).map_err(Error::Yaml)
+}
+
+/// Converts a TOML document to gron.
+#[cfg(feature = "toml")]
+pub fn toml_to_gron(input: &str) -> Result<String, Error> {
+    transcode_to_string(toml::Deserializer::new(input))
+}
+
+/// Converts gron statements to a TOML document. The root must be an object.
+#[cfg(feature = "toml")]
+pub fn gron_to_toml(input: &str) -> Result<String, Error> {
+    toml::to_string(&from_str::<Value>(input)?).map_err(Error::Toml)
+}
+
+/// Converts a CBOR document to gron.
+#[cfg(feature = "cbor")]
+pub fn cbor_to_gron(input: &[u8]) -> Result<String, Error> {
+    let Gron(gron) = ciborium::from_reader(input).map_err(Error::CborDecode)?;
+    Ok(gron)
+}
+
+/// The gron output of a document, written while the document is deserialized.
+///
+/// `ciborium` keeps its deserializer private and only hands it to a `Deserialize`
+/// implementation, so this one transcodes whatever deserializer it is given.
+#[cfg(feature = "cbor")]
+struct Gron(String);
+
+#[cfg(feature = "cbor")]
+impl<'de> serde::Deserialize<'de> for Gron {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        transcode_to_string(deserializer)
+            .map(Gron)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Converts gron statements to a CBOR document.
+#[cfg(feature = "cbor")]
+pub fn gron_to_cbor(input: &str) -> Result<Vec<u8>, Error> {
+    let mut writer = vec![];
+    ciborium::ser::into_writer(&from_str::<Value>(input)?, &mut writer).map_err(Error::Cbor)?;
+    Ok(writer)
+}
+
+/// Converts a MessagePack document to gron.
+#[cfg(feature = "msgpack")]
+pub fn msgpack_to_gron(input: &[u8]) -> Result<String, Error> {
+    transcode_to_string(&mut rmp_serde::Deserializer::new(input))
+}
+
+/// Converts gron statements to a MessagePack document. Structs are written as maps.
+#[cfg(feature = "msgpack")]
+pub fn gron_to_msgpack(input: &str) -> Result<Vec<u8>, Error> {
+    rmp_serde::to_vec_named(&from_str::<Value>(input)?).map_err(Error::MessagePack)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml() {
+        let gron = yaml_to_gron("a: 1\nb:\n  - x\n  - true\n").unwrap();
+        assert_eq!(
+            gron,
+            "json = {};
+json.a = 1;
+json.b = [];
+json.b[0] = \"x\";
+json.b[1] = true;
+"
+        );
+        assert_eq!(gron_to_yaml(&gron).unwrap(), "a: 1\nb:\n- x\n- true\n");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml() {
+        let gron = toml_to_gron("[package]\nname = \"x\"\n").unwrap();
+        assert_eq!(
+            gron,
+            "json = {};
+json.package = {};
+json.package.name = \"x\";
+"
+        );
+        assert_eq!(gron_to_toml(&gron).unwrap(), "[package]\nname = \"x\"\n");
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor() {
+        let gron = "json = {};\njson.a = [];\njson.a[0] = 1;\n";
+        assert_eq!(cbor_to_gron(&gron_to_cbor(gron).unwrap()).unwrap(), gron);
+
+        let value = serde_json::json!({ "a": [1, -2.5, null, true], "b": { "c-d": "e" } });
+        let mut cbor = vec![];
+        ciborium::ser::into_writer(&value, &mut cbor).unwrap();
+        assert_eq!(
+            cbor_to_gron(&cbor).unwrap(),
+            crate::to_string(&value).unwrap()
+        );
+        assert!(matches!(
+            cbor_to_gron(&cbor[..cbor.len() - 1]),
+            Err(Error::CborDecode(_))
+        ));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack() {
+        let gron = "json = {};\njson.a = [];\njson.a[0] = \"b\";\n";
+        assert_eq!(
+            msgpack_to_gron(&gron_to_msgpack(gron).unwrap()).unwrap(),
+            gron
+        );
+    }
+}
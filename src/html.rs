@@ -0,0 +1,239 @@
+//! HTML output for embedding colored statements in web pages.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Write as _};
+
+use crate::{
+    languages::serialize,
+    ser::{is_identifier, Formatter, LineSyntax, NamespaceKey},
+    write::{write_json_string, FmtWriter},
+    Error, Options, Write,
+};
+
+/// Default colors for the classes written by [`to_html_writer`], matching the terminal
+/// colors.
+pub const HTML_STYLESHEET: &str = include_str!("../assets/gron.css");
+
+/// Wraps each part of a statement in a `<span class="gron-...">`, where the class is one of
+/// `gron-key`, `gron-bracket`, `gron-number`, `gron-string`, `gron-bool` and `gron-null`.
+///
+/// [`Aligned`](crate::Aligned) measures keys including their markup, so wrapping this
+/// formatter in it only lines up keys made of the same kinds of segments.
+#[derive(Debug, Default)]
+pub struct HtmlFormatter {
+    syntax: LineSyntax,
+}
+
+impl HtmlFormatter {
+    pub fn with_syntax(syntax: LineSyntax) -> Self {
+        HtmlFormatter { syntax }
+    }
+}
+
+impl<W: Write> Formatter<W> for HtmlFormatter {
+    fn write_root(&mut self, key: &mut String, ns_root: &str) {
+        push_span(key, "key", ns_root);
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        match ns {
+            NamespaceKey::Array(n) => {
+                push_span(key, "bracket", "[");
+                push_span(key, "number", &n.to_string());
+                push_span(key, "bracket", "]");
+            }
+            NamespaceKey::Object(k) if is_identifier(k) => {
+                key.push('.');
+                push_span(key, "key", k);
+            }
+            NamespaceKey::Object(k) => {
+                let mut quoted = String::new();
+                write_json_string(k, |s| quoted.write_str(s)).unwrap();
+                push_span(key, "bracket", "[");
+                push_span(key, "string", &quoted);
+                push_span(key, "bracket", "]");
+            }
+        }
+    }
+
+    fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str(self.syntax.delimiter())
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str(self.syntax.end_of_line())
+    }
+
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+        write_span(writer, "null", "null")
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        write_span(writer, "bool", &value.to_string())
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        write_span(writer, "number", &value.to_string())
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        let mut quoted = String::new();
+        write_json_string(value, |s| quoted.write_str(s)).unwrap();
+        write_span(writer, "string", &quoted)
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        write_span(writer, "bracket", "[]")
+    }
+
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        write_span(writer, "bracket", "{}")
+    }
+
+    fn write_json(&mut self, writer: &mut W, value: &Value) -> Result<(), Error> {
+        let mut s = String::new();
+        escape(&mut s, &value.to_string());
+        writer.write_str(&s)
+    }
+}
+
+fn push_span(out: &mut String, class: &str, text: &str) {
+    write!(out, "<span class=\"gron-{class}\">").unwrap();
+    escape(out, text);
+    out.push_str("</span>");
+}
+
+fn write_span<W: Write>(writer: &mut W, class: &str, text: &str) -> Result<(), Error> {
+    let mut s = String::new();
+    push_span(&mut s, class, text);
+    writer.write_str(&s)
+}
+
+fn escape(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+pub fn to_html_string(value: &impl Serialize, options: &Options) -> Result<String, Error> {
+    let mut s = String::new();
+    to_html_writer(value, FmtWriter(&mut s), options)?;
+    Ok(s)
+}
+
+/// Writes `value` as HTML-escaped statements with each part wrapped in a `<span>`.
+///
+/// The output is meant for a `<pre class="gron">` element styled with [`HTML_STYLESHEET`] or
+/// your own rules for the `gron-*` classes. The format type and alignment of `options` are
+/// ignored.
+///
+/// ```
+/// use serde_gron::Options;
+///
+/// let html = serde_gron::to_html_string(&"<b>", &Options::default()).unwrap();
+/// assert_eq!(
+///     html,
+///     "<span class=\"gron-key\">json</span> = \
+///      <span class=\"gron-string\">&quot;&lt;b&gt;&quot;</span>;\n"
+/// );
+/// ```
+pub fn to_html_writer(
+    value: &impl Serialize,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    serialize(
+        value,
+        writer,
+        HtmlFormatter::with_syntax(options.syntax),
+        options,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{Aligned, Alignment, Serializer};
+
+    #[test]
+    fn test_html() {
+        let value = json!({ "a": [true, null], "b&c": 1.5 });
+        let html = to_html_string(&value, &Options::default()).unwrap();
+        let expected = [
+            concat!(
+                r#"<span class="gron-key">json</span> = "#,
+                r#"<span class="gron-bracket">{}</span>;"#,
+            ),
+            concat!(
+                r#"<span class="gron-key">json</span>.<span class="gron-key">a</span> = "#,
+                r#"<span class="gron-bracket">[]</span>;"#,
+            ),
+            concat!(
+                r#"<span class="gron-key">json</span>.<span class="gron-key">a</span>"#,
+                r#"<span class="gron-bracket">[</span><span class="gron-number">0</span>"#,
+                r#"<span class="gron-bracket">]</span> = <span class="gron-bool">true</span>;"#,
+            ),
+            concat!(
+                r#"<span class="gron-key">json</span>.<span class="gron-key">a</span>"#,
+                r#"<span class="gron-bracket">[</span><span class="gron-number">1</span>"#,
+                r#"<span class="gron-bracket">]</span> = <span class="gron-null">null</span>;"#,
+            ),
+            concat!(
+                r#"<span class="gron-key">json</span><span class="gron-bracket">[</span>"#,
+                r#"<span class="gron-string">&quot;b&amp;c&quot;</span>"#,
+                r#"<span class="gron-bracket">]</span> = <span class="gron-number">1.5</span>;"#,
+            ),
+        ];
+        assert_eq!(html.lines().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_html_formatter() {
+        let value = json!({ "a": 1, "bc": 2 });
+        let options = Options {
+            align: Some(Alignment::Container),
+            ..Options::default()
+        };
+        assert_eq!(
+            to_html_string(&value, &options).unwrap(),
+            to_html_string(&value, &Options::default()).unwrap()
+        );
+
+        let formatter = Aligned::new(HtmlFormatter::default(), Alignment::Container);
+        let mut s = String::new();
+        let mut ser = Serializer::with_formatter(FmtWriter(&mut s), formatter, "json");
+        value.serialize(&mut ser).unwrap();
+        ser.end_document().unwrap();
+        drop(ser);
+        assert!(s.contains(r#"<span class="gron-key">a</span>  = "#));
+    }
+
+    #[test]
+    fn test_html_collapsed() {
+        let options = Options {
+            collapse_depth: Some(0),
+            ..Options::default()
+        };
+        assert_eq!(
+            to_html_string(&json!({ "<": "'" }), &options).unwrap(),
+            "<span class=\"gron-key\">json</span> = {&quot;&lt;&quot;:&quot;&#39;&quot;};\n"
+        );
+    }
+}
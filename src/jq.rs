@@ -0,0 +1,241 @@
+//! Paths and programs for `jq`, e.g. `.a."b c"[0] = 1` and `setpath(["a", "b c", 0]; 1)`.
+
+use serde::Serialize;
+
+use alloc::string::String;
+use core::fmt::{Display, Write as _};
+
+use crate::{
+    languages::serialize,
+    ser::{is_identifier, Formatter, NamespaceKey},
+    write::{write_json_string, FmtWriter},
+    Error, Options, Write,
+};
+
+/// Writes statements whose keys are `jq` paths: the root is `.` and keys that are not
+/// identifiers are quoted, as in `.a."b c"[0] = 1`.
+#[derive(Debug, Default)]
+struct JqPathFormatter;
+
+impl<W: Write> Formatter<W> for JqPathFormatter {
+    fn write_root(&mut self, _key: &mut String, _ns_root: &str) {}
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        match ns {
+            NamespaceKey::Array(n) => {
+                if key.is_empty() {
+                    key.push('.');
+                }
+                write!(key, "[{n}]").unwrap();
+            }
+            NamespaceKey::Object(k) => {
+                key.push('.');
+                if is_identifier(k) {
+                    key.push_str(k);
+                } else {
+                    write_json_string(k, |s| key.write_str(s)).unwrap();
+                }
+            }
+        }
+    }
+
+    fn write_key(&mut self, writer: &mut W, key: &str) -> Result<(), Error> {
+        writer.write_str(if key.is_empty() { "." } else { key })
+    }
+
+    fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str(" = ")
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("\n")
+    }
+
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("null")
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        write_json_string(value, |s| writer.write_str(s))
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("[]")
+    }
+
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("{}")
+    }
+}
+
+/// Writes one `| setpath([...]; value)` line per leaf.
+#[derive(Debug, Default)]
+struct JqProgramFormatter;
+
+impl<W: Write> Formatter<W> for JqProgramFormatter {
+    fn write_root(&mut self, key: &mut String, _ns_root: &str) {
+        key.push_str("| setpath([");
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        if !key.ends_with('[') {
+            key.push_str(", ");
+        }
+        match ns {
+            NamespaceKey::Array(n) => write!(key, "{n}").unwrap(),
+            NamespaceKey::Object(k) => write_json_string(k, |s| key.write_str(s)).unwrap(),
+        }
+    }
+
+    fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("]; ")
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str(")\n")
+    }
+
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("null")
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        write_json_string(value, |s| writer.write_str(s))
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("[]")
+    }
+
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("{}")
+    }
+}
+
+pub fn to_jq_string(value: &impl Serialize, options: &Options) -> Result<String, Error> {
+    let mut s = String::new();
+    to_jq_writer(value, FmtWriter(&mut s), options)?;
+    Ok(s)
+}
+
+/// Writes `value` as statements keyed by `jq` paths, so any key can be pasted into a `jq`
+/// filter.
+///
+/// The root name of `options` is ignored, since `jq` paths start at `.`.
+///
+/// ```
+/// use serde_gron::Options;
+/// use serde_json::json;
+///
+/// let jq = serde_gron::to_jq_string(&json!({ "a b": [1] }), &Options::default());
+/// assert_eq!(jq.unwrap(), ". = {}\n.\"a b\" = []\n.\"a b\"[0] = 1\n");
+/// ```
+pub fn to_jq_writer(
+    value: &impl Serialize,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    serialize(value, writer, JqPathFormatter, options)
+}
+
+pub fn to_jq_program_string(value: &impl Serialize, options: &Options) -> Result<String, Error> {
+    let mut s = String::new();
+    to_jq_program_writer(value, FmtWriter(&mut s), options)?;
+    Ok(s)
+}
+
+/// Writes a `jq` program that rebuilds `value` from `null`, one `setpath` per leaf, e.g. for
+/// `jq -n -f program.jq`.
+///
+/// The output is always leaf-only, since `setpath` creates the containers above a path.
+///
+/// ```
+/// use serde_gron::Options;
+/// use serde_json::json;
+///
+/// let jq = serde_gron::to_jq_program_string(&json!({ "a": [1, {}] }), &Options::default());
+/// assert_eq!(
+///     jq.unwrap(),
+///     "null\n| setpath([\"a\", 0]; 1)\n| setpath([\"a\", 1]; {})\n"
+/// );
+/// ```
+pub fn to_jq_program_writer(
+    value: &impl Serialize,
+    mut writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    writer.write_str("null\n")?;
+    let options = Options {
+        leaf_only: true,
+        ..options.clone()
+    };
+    serialize(value, writer, JqProgramFormatter, &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_jq_paths() {
+        let value = json!({ "a": [{ "b-c": null }], "~d": { "e": true } });
+        assert_eq!(
+            to_jq_string(&value, &Options::default()).unwrap(),
+            r#". = {}
+.a = []
+.a[0] = {}
+.a[0]."b-c" = null
+."~d" = {}
+."~d".e = true
+"#
+        );
+        assert_eq!(
+            to_jq_string(&json!([[]]), &Options::default()).unwrap(),
+            ". = []\n.[0] = []\n"
+        );
+        assert_eq!(to_jq_string(&1, &Options::default()).unwrap(), ". = 1\n");
+    }
+
+    #[test]
+    fn test_jq_program() {
+        let value = json!({ "a": [{ "b\"c": "x" }, []], "d": 1.5 });
+        assert_eq!(
+            to_jq_program_string(&value, &Options::default()).unwrap(),
+            r#"null
+| setpath(["a", 0, "b\"c"]; "x")
+| setpath(["a", 1]; [])
+| setpath(["d"]; 1.5)
+"#
+        );
+        assert_eq!(
+            to_jq_program_string(&"x", &Options::default()).unwrap(),
+            "null\n| setpath([]; \"x\")\n"
+        );
+    }
+}
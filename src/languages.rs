@@ -0,0 +1,485 @@
+//! Statements that run as JavaScript, Python or shell, e.g. for generating test fixtures.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Write as _};
+
+use crate::{
+    ser::{is_float, is_identifier, Formatter, NamespaceKey, Serializer},
+    write::{write_json_string, FmtWriter},
+    Error, Options, Write,
+};
+
+/// Writes `const json = {};` for the root and `json.a = 1;` for everything below it.
+///
+/// Gron statements are already JavaScript, so this only differs from the regular output in
+/// declaring the root.
+#[derive(Debug, Default)]
+struct JavaScriptFormatter;
+
+impl<W: Write> Formatter<W> for JavaScriptFormatter {
+    fn write_root(&mut self, key: &mut String, ns_root: &str) {
+        key.push_str(ns_root);
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        match ns {
+            NamespaceKey::Array(n) => write!(key, "[{n}]").unwrap(),
+            NamespaceKey::Object(k) if is_identifier(k) => write!(key, ".{k}").unwrap(),
+            NamespaceKey::Object(k) => {
+                key.push('[');
+                write_json_string(k, |s| key.write_str(s)).unwrap();
+                key.push(']');
+            }
+        }
+    }
+
+    fn write_key(&mut self, writer: &mut W, key: &str) -> Result<(), Error> {
+        // Segments always start with `.` or `[`, so a key without them is the root.
+        if !key.contains(['.', '[']) {
+            writer.write_str("const ")?;
+        }
+        writer.write_str(key)
+    }
+
+    fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str(" = ")
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str(";\n")
+    }
+
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("null")
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        write_json_string(value, |s| writer.write_str(s))
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("[]")
+    }
+
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("{}")
+    }
+}
+
+/// Writes `json = {}` and `json["a"] = 1`, with `True`, `False` and `None`.
+#[derive(Debug, Default)]
+struct PythonFormatter;
+
+impl<W: Write> Formatter<W> for PythonFormatter {
+    fn write_root(&mut self, key: &mut String, ns_root: &str) {
+        key.push_str(ns_root);
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        match ns {
+            NamespaceKey::Array(n) => write!(key, "[{n}]").unwrap(),
+            NamespaceKey::Object(k) => {
+                key.push('[');
+                write_json_string(k, |s| key.write_str(s)).unwrap();
+                key.push(']');
+            }
+        }
+    }
+
+    fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str(" = ")
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("\n")
+    }
+
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("None")
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        writer.write_str(if value { "True" } else { "False" })
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        if !is_float::<N>() {
+            return write!(writer, "{value}");
+        }
+        // `Display` writes `1.0` as `1`, which Python reads as an int, so write floats the way
+        // `write_json` does.
+        let value: f64 = value.to_string().parse().map_err(|_| Error::Fmt)?;
+        match serde_json::Number::from_f64(value) {
+            Some(n) => write!(writer, "{n}"),
+            None => write!(writer, "float(\"{value}\")"),
+        }
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        // Every JSON string escape is also a Python string escape.
+        write_json_string(value, |s| writer.write_str(s))
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("[]")
+    }
+
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("{}")
+    }
+
+    fn write_json(&mut self, writer: &mut W, value: &Value) -> Result<(), Error> {
+        match value {
+            Value::Null => self.write_null(writer),
+            Value::Bool(b) => self.write_bool(writer, *b),
+            Value::Number(n) => write!(writer, "{n}"),
+            Value::String(s) => self.write_string(writer, s),
+            Value::Array(array) => {
+                writer.write_str("[")?;
+                for (i, v) in array.iter().enumerate() {
+                    if i > 0 {
+                        writer.write_str(", ")?;
+                    }
+                    self.write_json(writer, v)?;
+                }
+                writer.write_str("]")
+            }
+            Value::Object(object) => {
+                writer.write_str("{")?;
+                for (i, (k, v)) in object.iter().enumerate() {
+                    if i > 0 {
+                        writer.write_str(", ")?;
+                    }
+                    self.write_string(writer, k)?;
+                    writer.write_str(": ")?;
+                    self.write_json(writer, v)?;
+                }
+                writer.write_str("}")
+            }
+        }
+    }
+}
+
+/// Writes `json['a.b[0]']='x'` assignments to a Bash associative array, one per leaf.
+///
+/// Subscripts are the gron path below the root. Every value is a string to the shell, so
+/// `null` is written as an empty string and collapsed containers as their JSON text.
+#[derive(Debug, Default)]
+struct ShellFormatter;
+
+impl<W: Write> Formatter<W> for ShellFormatter {
+    fn write_root(&mut self, key: &mut String, ns_root: &str) {
+        key.push_str(ns_root);
+        key.push_str("['");
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        let first = key.ends_with("['");
+        let mut segment = String::new();
+        match ns {
+            NamespaceKey::Array(n) => write!(segment, "[{n}]").unwrap(),
+            NamespaceKey::Object(k) if is_identifier(k) && first => segment.push_str(k),
+            NamespaceKey::Object(k) if is_identifier(k) => write!(segment, ".{k}").unwrap(),
+            NamespaceKey::Object(k) => {
+                segment.push('[');
+                write_json_string(k, |s| segment.write_str(s)).unwrap();
+                segment.push(']');
+            }
+        }
+        key.push_str(&segment.replace('\'', "'\\''"));
+    }
+
+    fn write_key(&mut self, writer: &mut W, key: &str) -> Result<(), Error> {
+        writer.write_str(key)?;
+        writer.write_str("']")
+    }
+
+    fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("=")
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("\n")
+    }
+
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("''")
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        write!(writer, "'{}'", value.replace('\'', "'\\''"))
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("'[]'")
+    }
+
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("'{}'")
+    }
+
+    fn write_json(&mut self, writer: &mut W, value: &Value) -> Result<(), Error> {
+        self.write_string(writer, &value.to_string())
+    }
+}
+
+pub fn to_javascript_string(value: &impl Serialize, options: &Options) -> Result<String, Error> {
+    let mut s = String::new();
+    to_javascript_writer(value, FmtWriter(&mut s), options)?;
+    Ok(s)
+}
+
+/// Writes `value` as JavaScript statements that rebuild it in a variable named after the
+/// root.
+///
+/// The root name and the collapse settings of `options` are honoured. `leaf_only` is
+/// ignored, since assigning to `json.a[0]` fails unless `json.a` was created first, and the
+/// line syntax is fixed.
+///
+/// ```
+/// use serde_gron::Options;
+/// use serde_json::json;
+///
+/// let js = serde_gron::to_javascript_string(&json!({ "a": [1] }), &Options::default());
+/// assert_eq!(js.unwrap(), "const json = {};\njson.a = [];\njson.a[0] = 1;\n");
+/// ```
+pub fn to_javascript_writer(
+    value: &impl Serialize,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    serialize(
+        value,
+        writer,
+        JavaScriptFormatter,
+        &with_containers(options),
+    )
+}
+
+pub fn to_python_string(value: &impl Serialize, options: &Options) -> Result<String, Error> {
+    let mut s = String::new();
+    to_python_writer(value, FmtWriter(&mut s), options)?;
+    Ok(s)
+}
+
+/// Writes `value` as Python statements that rebuild it in a variable named after the root.
+///
+/// Object keys are always subscripted, since Python dictionaries have no attribute access.
+/// As for [`to_javascript_writer`], `leaf_only` is ignored.
+///
+/// ```
+/// use serde_gron::Options;
+/// use serde_json::json;
+///
+/// let py = serde_gron::to_python_string(&json!({ "a": [true, null] }), &Options::default());
+/// assert_eq!(
+///     py.unwrap(),
+///     "json = {}\njson[\"a\"] = []\njson[\"a\"][0] = True\njson[\"a\"][1] = None\n"
+/// );
+/// ```
+pub fn to_python_writer(
+    value: &impl Serialize,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    serialize(value, writer, PythonFormatter, &with_containers(options))
+}
+
+pub fn to_shell_string(value: &impl Serialize, options: &Options) -> Result<String, Error> {
+    let mut s = String::new();
+    to_shell_writer(value, FmtWriter(&mut s), options)?;
+    Ok(s)
+}
+
+/// Writes `value` as a Bash `declare -A` associative array named after the root, keyed by
+/// the path of each leaf.
+///
+/// Bash arrays cannot nest, so the output is always leaf-only.
+///
+/// ```
+/// use serde_gron::Options;
+/// use serde_json::json;
+///
+/// let sh = serde_gron::to_shell_string(&json!({ "a": ["it's"] }), &Options::default());
+/// assert_eq!(sh.unwrap(), "declare -A json\njson['a[0]']='it'\\''s'\n");
+/// ```
+pub fn to_shell_writer(
+    value: &impl Serialize,
+    mut writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    writeln!(writer, "declare -A {}", options.root_name)?;
+    let options = Options {
+        leaf_only: true,
+        ..options.clone()
+    };
+    serialize(value, writer, ShellFormatter, &options)
+}
+
+/// Returns `options` without `leaf_only`, for output that must create containers before
+/// assigning to them.
+fn with_containers(options: &Options) -> Options {
+    Options {
+        leaf_only: false,
+        ..options.clone()
+    }
+}
+
+/// Serializes `value` with `formatter`, building a `Value` first if `options` collapse.
+pub(crate) fn serialize<W: Write, F: Formatter<W>>(
+    value: &impl Serialize,
+    writer: W,
+    formatter: F,
+    options: &Options,
+) -> Result<(), Error> {
+    let mut ser = Serializer::with_options(writer, formatter, options);
+    if options.collapses() {
+        // Collapsing depends on the size of each subtree, so build the whole value first.
+        let value = serde_json::to_value(value).map_err(Error::Serialize)?;
+        ser.serialize_json_value(&value)?;
+    } else {
+        value.serialize(&mut ser)?;
+    }
+    ser.end_document()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn value() -> Value {
+        json!({ "a": [1.5, { "b-c": "d\n" }], "e": { "f": false, "g": null }, "h": [] })
+    }
+
+    #[test]
+    fn test_javascript() {
+        assert_eq!(
+            to_javascript_string(&value(), &Options::default()).unwrap(),
+            r#"const json = {};
+json.a = [];
+json.a[0] = 1.5;
+json.a[1] = {};
+json.a[1]["b-c"] = "d\n";
+json.e = {};
+json.e.f = false;
+json.e.g = null;
+json.h = [];
+"#
+        );
+        assert_eq!(
+            to_javascript_string(&1, &Options::new("x", Default::default())).unwrap(),
+            "const x = 1;\n"
+        );
+    }
+
+    #[test]
+    fn test_python() {
+        let options = Options {
+            collapse_depth: Some(1),
+            ..Options::default()
+        };
+        assert_eq!(
+            to_python_string(&value(), &options).unwrap(),
+            r#"json = {}
+json["a"] = [1.5, {"b-c": "d\n"}]
+json["e"] = {"f": False, "g": None}
+json["h"] = []
+"#
+        );
+    }
+
+    #[test]
+    fn test_python_floats() {
+        let value = json!({ "a": 1.0, "b": 2, "c": 1e300, "d": -0.5 });
+        let expected = "json = {}\njson[\"a\"] = 1.0\njson[\"b\"] = 2\njson[\"c\"] = 1e+300\n\
+                        json[\"d\"] = -0.5\n";
+        assert_eq!(
+            to_python_string(&value, &Options::default()).unwrap(),
+            expected
+        );
+        let options = Options {
+            collapse_depth: Some(0),
+            ..Options::default()
+        };
+        assert_eq!(
+            to_python_string(&value, &options).unwrap(),
+            "json = {\"a\": 1.0, \"b\": 2, \"c\": 1e+300, \"d\": -0.5}\n"
+        );
+        assert_eq!(
+            to_python_string(&[f64::NAN, f64::NEG_INFINITY], &Options::default()).unwrap(),
+            "json = []\njson[0] = float(\"NaN\")\njson[1] = float(\"-inf\")\n"
+        );
+        assert_eq!(
+            to_python_string(&(1u8, 0.25f32), &Options::default()).unwrap(),
+            "json = []\njson[0] = 1\njson[1] = 0.25\n"
+        );
+    }
+
+    #[test]
+    fn test_languages_ignore_leaf_only() {
+        let options = Options {
+            leaf_only: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            to_javascript_string(&value(), &options).unwrap(),
+            to_javascript_string(&value(), &Options::default()).unwrap()
+        );
+        assert_eq!(
+            to_python_string(&value(), &options).unwrap(),
+            to_python_string(&value(), &Options::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_shell() {
+        assert_eq!(
+            to_shell_string(&value(), &Options::default()).unwrap(),
+            r#"declare -A json
+json['a[0]']=1.5
+json['a[1]["b-c"]']='d
+'
+json['e.f']=false
+json['e.g']=''
+json['h']='[]'
+"#
+        );
+        assert_eq!(
+            to_shell_string(&json!({ "it's": 1 }), &Options::default()).unwrap(),
+            "declare -A json\njson['[\"it'\\''s\"]']=1\n"
+        );
+    }
+}
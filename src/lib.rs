@@ -1,12 +1,87 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod align;
+mod apply;
+#[cfg(feature = "tokio")]
+mod async_io;
+mod color;
+mod csv;
+mod de;
+mod env;
+mod error;
+#[cfg(any(
+    feature = "yaml",
+    feature = "toml",
+    feature = "cbor",
+    feature = "msgpack"
+))]
+mod formats;
+mod html;
+mod jq;
+mod languages;
+mod patch;
+mod path;
+mod properties;
 mod ser;
+mod statements;
+mod string_value;
+mod transcode;
+mod tree;
+mod write;
 
+pub use align::{Aligned, Alignment};
+pub use apply::{apply, apply_str, from_pairs, unflatten};
+#[cfg(feature = "tokio")]
+pub use async_io::{
+    from_async_reader, to_async_writer, to_async_writer_with, to_async_writer_with_options,
+    value_to_async_writer, value_to_async_writer_with, value_to_async_writer_with_options,
+};
+pub use color::{Color, ColorDepth, ColorTheme, Style};
+pub use csv::{
+    from_csv_str, from_tsv_str, to_csv_string, to_csv_writer, to_tsv_string, to_tsv_writer,
+};
+pub use de::{from_str, parse_statement, parse_str, Statement};
+#[cfg(feature = "std")]
+pub use env::from_env;
+pub use env::{from_env_str, from_env_vars, to_env_string, to_env_writer, EnvCase, EnvOptions};
+pub use error::Error;
+#[cfg(feature = "cbor")]
+pub use formats::{cbor_to_gron, gron_to_cbor};
+#[cfg(feature = "msgpack")]
+pub use formats::{gron_to_msgpack, msgpack_to_gron};
+#[cfg(feature = "toml")]
+pub use formats::{gron_to_toml, toml_to_gron};
+#[cfg(feature = "yaml")]
+pub use formats::{gron_to_yaml, yaml_to_gron};
+pub use html::{to_html_string, to_html_writer, HtmlFormatter, HTML_STYLESHEET};
+pub use jq::{to_jq_program_string, to_jq_program_writer, to_jq_string, to_jq_writer};
+pub use languages::{
+    to_javascript_string, to_javascript_writer, to_python_string, to_python_writer,
+    to_shell_string, to_shell_writer,
+};
+pub use patch::{diff_json_patch, diff_merge_patch, json_patch_to_statements, PatchOperation};
+pub use path::Path;
+pub use properties::{from_properties_str, to_properties_string, to_properties_writer};
 pub use ser::{
-    to_colored_string, to_colored_writer, to_string, to_string_with, to_writer, to_writer_with,
-    FormatType,
+    to_colored_string, to_colored_writer, to_string, to_string_with, to_string_with_options,
+    to_writer, to_writer_with, to_writer_with_options, value_to_string, value_to_writer,
+    value_to_writer_with, value_to_writer_with_options, ColorFormatter, FormatType, Formatter,
+    LineSyntax, LineTerminator, NamespaceKey, Options, RegularFormatter, Serializer,
 };
+#[cfg(feature = "std")]
+pub use ser::{Stateless, StatelessFormatter};
+pub use statements::{to_statements, to_statements_with, value_to_statements, Statements};
+pub use transcode::{transcode, transcode_with, transcode_with_options};
+pub use tree::{to_tree_string, to_tree_writer};
+#[cfg(feature = "std")]
+pub use write::Terminal;
+pub use write::{FmtWriter, Write};
 
 #[cfg(test)]
 mod tests {
+    use alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
     use serde_json::json;
 
     use super::*;
@@ -21,6 +96,19 @@ mod tests {
         assert_eq!(to_string(&json!("abc")).unwrap(), "json = \"abc\";\n");
     }
 
+    #[test]
+    fn test_bool() {
+        assert_eq!(to_string(&json!(true)).unwrap(), "json = true;\n");
+    }
+
+    #[test]
+    fn test_escaped_string() {
+        assert_eq!(
+            to_string(&json!("a\"b\n")).unwrap(),
+            "json = \"a\\\"b\\n\";\n"
+        );
+    }
+
     #[test]
     fn test_number() {
         assert_eq!(to_string(&json!(1)).unwrap(), "json = 1;\n");
@@ -75,6 +163,19 @@ json.e = 4;
 "
         );
 
+        assert_eq!(
+            to_string(&json!([{ "a": [1, { "b": 2 }] }, { "c": 3 }])).unwrap(),
+            "json = [];
+json[0] = {};
+json[0].a = [];
+json[0].a[0] = 1;
+json[0].a[1] = {};
+json[0].a[1].b = 2;
+json[1] = {};
+json[1].c = 3;
+"
+        );
+
         assert_eq!(
             to_string(&json!({ "a-b-c": 1 })).unwrap(),
             "json = {};
@@ -82,4 +183,309 @@ json[\"a-b-c\"] = 1;
 "
         );
     }
+
+    #[test]
+    fn test_enum_variants() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        enum E {
+            Unit,
+            Newtype(u8),
+            A(u8, u8),
+            C(u8, u8),
+            B { x: u8 },
+        }
+
+        let values = [
+            E::Unit,
+            E::Newtype(1),
+            E::A(1, 2),
+            E::C(1, 2),
+            E::B { x: 1 },
+        ];
+        let s = to_string(&values).unwrap();
+        assert_eq!(
+            s,
+            r#"json = [];
+json[0] = "Unit";
+json[1] = {};
+json[1].Newtype = 1;
+json[2] = {};
+json[2].A = [];
+json[2].A[0] = 1;
+json[2].A[1] = 2;
+json[3] = {};
+json[3].C = [];
+json[3].C[0] = 1;
+json[3].C[1] = 2;
+json[4] = {};
+json[4].B = {};
+json[4].B.x = 1;
+"#
+        );
+        assert_eq!(from_str::<Vec<E>>(&s).unwrap(), values);
+
+        let options = Options {
+            leaf_only: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            to_string_with_options(&E::A(1, 2), &options).unwrap(),
+            "json.A[0] = 1;\njson.A[1] = 2;\n"
+        );
+    }
+
+    #[test]
+    fn test_map_keys() {
+        let map: BTreeMap<_, _> = [(1, "a"), (20, "b")].into();
+        assert_eq!(
+            to_string(&map).unwrap(),
+            "json = {};
+json[\"1\"] = \"a\";
+json[\"20\"] = \"b\";
+"
+        );
+
+        assert_eq!(
+            to_string(&json!({ "a\"": 1 })).unwrap(),
+            "json = {};\njson[\"a\\\"\"] = 1;\n"
+        );
+    }
+
+    #[test]
+    fn test_value_to_string() {
+        let value = json!({ "a": [1, -2, 3.5, "x", null, true], "b-c": { "d": {} }, "e": [] });
+        assert_eq!(value_to_string(&value).unwrap(), to_string(&value).unwrap());
+    }
+
+    #[test]
+    fn test_raw_value() {
+        #[derive(serde::Serialize)]
+        struct Wrapper {
+            raw: Box<serde_json::value::RawValue>,
+        }
+
+        let raw = serde_json::value::RawValue::from_string(r#"{"a": [1, 2]}"#.into()).unwrap();
+        assert_eq!(
+            to_string(&Wrapper { raw }).unwrap(),
+            "json = {};
+json.raw = {};
+json.raw.a = [];
+json.raw.a[0] = 1;
+json.raw.a[1] = 2;
+"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_auto_color_follows_writer() {
+        if ["NO_COLOR", "CLICOLOR_FORCE"]
+            .iter()
+            .any(|name| std::env::var_os(name).is_some())
+        {
+            return;
+        }
+
+        let value = json!([1]);
+        let plain = to_string(&value).unwrap();
+        assert_eq!(
+            to_string_with(&value, "json", FormatType::Auto).unwrap(),
+            plain
+        );
+        for (is_terminal, expected) in [(false, plain), (true, to_colored_string(&value).unwrap())]
+        {
+            let mut out = vec![];
+            let writer = Terminal::new(&mut out, is_terminal);
+            to_writer_with(&value, writer, "json", FormatType::Auto).unwrap();
+            assert_eq!(String::from_utf8(out).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_line_syntax() {
+        let value = json!({ "a": [true] });
+        let syntax = |spaced_delimiter, semicolon, terminator| LineSyntax {
+            spaced_delimiter,
+            semicolon,
+            terminator,
+        };
+        let cases = [
+            (
+                syntax(true, true, LineTerminator::CrLf),
+                "json = {};\r\njson.a = [];\r\njson.a[0] = true;\r\n",
+            ),
+            (
+                syntax(false, false, LineTerminator::Lf),
+                "json={}\njson.a=[]\njson.a[0]=true\n",
+            ),
+            (
+                syntax(true, false, LineTerminator::Nul),
+                "json = {}\0json.a = []\0json.a[0] = true\0",
+            ),
+        ];
+        for (syntax, expected) in cases {
+            let options = Options {
+                syntax,
+                ..Options::default()
+            };
+            let gron = to_string_with_options(&value, &options).unwrap();
+            assert_eq!(gron, expected);
+            assert_eq!(from_str::<serde_json::Value>(&gron).unwrap(), value);
+
+            let mut output = String::new();
+            value_to_writer_with_options(&value, FmtWriter(&mut output), &options).unwrap();
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn test_leaf_only() {
+        let value = json!({ "a": [{ "b": 1 }, [], {}], "c": {} });
+        let options = Options {
+            leaf_only: true,
+            ..Options::default()
+        };
+        let expected = "json.a[0].b = 1;
+json.a[1] = [];
+json.a[2] = {};
+json.c = {};
+";
+        assert_eq!(to_string_with_options(&value, &options).unwrap(), expected);
+        let mut output = String::new();
+        value_to_writer_with_options(&value, FmtWriter(&mut output), &options).unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(from_str::<serde_json::Value>(expected).unwrap(), value);
+
+        assert_eq!(
+            to_string_with_options(&json!([]), &options).unwrap(),
+            "json = [];\n"
+        );
+        assert_eq!(
+            to_string_with_options(&json!("x"), &options).unwrap(),
+            "json = \"x\";\n"
+        );
+    }
+
+    #[test]
+    fn test_collapse() {
+        let value = json!({
+            "a": { "b": [1, 2], "c": { "d": "e;" } },
+            "f": [{ "g": null }, []]
+        });
+
+        let options = Options {
+            collapse_depth: Some(2),
+            ..Options::default()
+        };
+        let gron = to_string_with_options(&value, &options).unwrap();
+        assert_eq!(
+            gron,
+            r#"json = {};
+json.a = {};
+json.a.b = [1,2];
+json.a.c = {"d":"e;"};
+json.f = [];
+json.f[0] = {"g":null};
+json.f[1] = [];
+"#
+        );
+        assert_eq!(from_str::<serde_json::Value>(&gron).unwrap(), value);
+
+        let options = Options {
+            collapse_leaves: Some(3),
+            ..Options::default()
+        };
+        let gron = to_string_with_options(&value, &options).unwrap();
+        assert_eq!(
+            gron,
+            r#"json = {};
+json.a = {};
+json.a.b = [1,2];
+json.a.c = {"d":"e;"};
+json.f = [{"g":null},[]];
+"#
+        );
+        assert_eq!(from_str::<serde_json::Value>(&gron).unwrap(), value);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", debug_assertions))]
+    #[should_panic(expected = "`Serializer::end_document` was not called")]
+    fn test_missing_end_document() {
+        let mut ser = Serializer::<_, RegularFormatter>::new(vec![]);
+        serde::Serialize::serialize(&json!({ "a": 1 }), &mut ser).unwrap();
+    }
+
+    #[test]
+    fn test_failed_serialization_needs_no_end_document() {
+        let mut ser = Serializer::<_, RegularFormatter>::new(FmtWriter(String::new()));
+        assert!(serde::Serialize::serialize(&[1u128], &mut ser).is_err());
+    }
+
+    #[test]
+    fn test_formatter_hooks() {
+        /// Numbers statements and brackets the document, delegating the rest.
+        #[derive(Default)]
+        struct Numbered {
+            inner: RegularFormatter,
+            line: usize,
+        }
+
+        impl<W: Write> Formatter<W> for Numbered {
+            fn write_root(&mut self, key: &mut String, ns_root: &str) {
+                Formatter::<W>::write_root(&mut self.inner, key, ns_root)
+            }
+            fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+                Formatter::<W>::write_key_segment(&mut self.inner, key, ns)
+            }
+            fn begin_document(&mut self, writer: &mut W) -> Result<(), Error> {
+                writer.write_str("BEGIN\n")
+            }
+            fn end_document(&mut self, writer: &mut W) -> Result<(), Error> {
+                writeln!(writer, "END {}", self.line)
+            }
+            fn begin_statement(&mut self, writer: &mut W) -> Result<(), Error> {
+                self.line += 1;
+                write!(writer, "{} ", self.line)
+            }
+            fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+                self.inner.write_key_value_delimiter(writer)
+            }
+            fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+                self.inner.write_end_of_line(writer)
+            }
+            fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+                self.inner.write_null(writer)
+            }
+            fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+                self.inner.write_bool(writer, value)
+            }
+            fn write_number<N: num::Num + core::fmt::Display>(
+                &mut self,
+                writer: &mut W,
+                value: N,
+            ) -> Result<(), Error> {
+                self.inner.write_number(writer, value)
+            }
+            fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+                self.inner.write_string(writer, value)
+            }
+            fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+                self.inner.write_init_array(writer)
+            }
+            fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+                self.inner.write_init_object(writer)
+            }
+        }
+
+        let mut s = String::new();
+        let mut ser = Serializer::<_, Numbered>::new(FmtWriter(&mut s));
+        serde::Serialize::serialize(&json!({ "a": [true] }), &mut ser).unwrap();
+        ser.end_document().unwrap();
+        drop(ser);
+        assert_eq!(
+            s,
+            "BEGIN\n1 json = {};\n2 json.a = [];\n3 json.a[0] = true;\nEND 3\n"
+        );
+    }
 }
@@ -0,0 +1,367 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use alloc::{format, string::String, vec, vec::Vec};
+
+use crate::{ser::NamespaceKey, statements::Statements, Error, Path, Statement};
+
+/// A single RFC 6902 JSON Patch operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// Returns the RFC 6902 JSON Patch that turns `from` into `to`.
+pub fn diff_json_patch(from: &Value, to: &Value) -> Vec<PatchOperation> {
+    let mut ops = vec![];
+    diff(&mut Path::new("json"), from, to, &mut ops);
+    ops
+}
+
+fn diff(path: &mut Path, from: &Value, to: &Value, ops: &mut Vec<PatchOperation>) {
+    match (from, to) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (k, v) in a.iter() {
+                path.push(NamespaceKey::Object(k.clone()));
+                match b.get(k) {
+                    Some(w) => diff(path, v, w, ops),
+                    None => ops.push(PatchOperation::Remove {
+                        path: path.to_json_pointer(),
+                    }),
+                }
+                path.pop();
+            }
+            for (k, w) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+                path.push(NamespaceKey::Object(k.clone()));
+                ops.push(PatchOperation::Add {
+                    path: path.to_json_pointer(),
+                    value: w.clone(),
+                });
+                path.pop();
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (i, (v, w)) in a.iter().zip(b.iter()).enumerate() {
+                path.push(NamespaceKey::Array(i));
+                diff(path, v, w, ops);
+                path.pop();
+            }
+            for (i, w) in b.iter().enumerate().skip(a.len()) {
+                path.push(NamespaceKey::Array(i));
+                ops.push(PatchOperation::Add {
+                    path: path.to_json_pointer(),
+                    value: w.clone(),
+                });
+                path.pop();
+            }
+            for i in (b.len()..a.len()).rev() {
+                path.push(NamespaceKey::Array(i));
+                ops.push(PatchOperation::Remove {
+                    path: path.to_json_pointer(),
+                });
+                path.pop();
+            }
+        }
+        (v, w) if v != w => ops.push(PatchOperation::Replace {
+            path: path.to_json_pointer(),
+            value: w.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Returns the RFC 7396 JSON Merge Patch that turns `from` into `to`.
+///
+/// Merge patches cannot express setting an object member to `null`; such members are
+/// removed instead.
+pub fn diff_merge_patch(from: &Value, to: &Value) -> Value {
+    match (from, to) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut patch = Map::new();
+            for k in a.keys().filter(|k| !b.contains_key(*k)) {
+                patch.insert(k.clone(), Value::Null);
+            }
+            for (k, w) in b.iter() {
+                match a.get(k) {
+                    Some(v) if v == w => {}
+                    Some(v) => {
+                        patch.insert(k.clone(), diff_merge_patch(v, w));
+                    }
+                    None => {
+                        patch.insert(k.clone(), w.clone());
+                    }
+                }
+            }
+            Value::Object(patch)
+        }
+        (_, w) => w.clone(),
+    }
+}
+
+/// Renders a JSON Patch as gron statements that have the same effect when passed to
+/// [`apply`](crate::apply) on `base`.
+///
+/// `base` is needed to tell array indexes from object keys and to resolve `move` and `copy`.
+/// Inserting into the middle of an array rewrites the whole array, since gron assignments
+/// cannot shift elements.
+pub fn json_patch_to_statements(
+    base: &Value,
+    patch: &[PatchOperation],
+) -> Result<Vec<Statement>, Error> {
+    let mut doc = base.clone();
+    let mut statements = vec![];
+
+    for op in patch {
+        match op {
+            PatchOperation::Add { path, value } => {
+                let path = resolve_pointer(&doc, path)?;
+                add(&mut doc, &path, value.clone(), &mut statements)?;
+            }
+            PatchOperation::Remove { path } => {
+                let path = resolve_pointer(&doc, path)?;
+                remove(&mut doc, &path)?;
+                statements.push(Statement::Delete(path));
+            }
+            PatchOperation::Replace { path, value } => {
+                let path = resolve_pointer(&doc, path)?;
+                if get_mut(&mut doc, &path.keys).is_none() {
+                    return Err(not_found(&path));
+                }
+                replace(&mut doc, &path, value.clone(), &mut statements)?;
+            }
+            PatchOperation::Move { from, path } => {
+                let from = resolve_pointer(&doc, from)?;
+                let value = remove(&mut doc, &from)?;
+                statements.push(Statement::Delete(from));
+                let path = resolve_pointer(&doc, path)?;
+                add(&mut doc, &path, value, &mut statements)?;
+            }
+            PatchOperation::Copy { from, path } => {
+                let from = resolve_pointer(&doc, from)?;
+                let value = get_mut(&mut doc, &from.keys)
+                    .ok_or_else(|| not_found(&from))?
+                    .clone();
+                let path = resolve_pointer(&doc, path)?;
+                add(&mut doc, &path, value, &mut statements)?;
+            }
+            PatchOperation::Test { path, value } => {
+                let path = resolve_pointer(&doc, path)?;
+                if get_mut(&mut doc, &path.keys).as_deref() != Some(value) {
+                    return Err(Error::Patch(format!("Test failed at `{path}`")));
+                }
+            }
+        }
+    }
+
+    Ok(statements)
+}
+
+fn not_found(path: &Path) -> Error {
+    Error::Patch(format!("`{path}` does not exist"))
+}
+
+fn resolve_pointer(doc: &Value, pointer: &str) -> Result<Path, Error> {
+    let mut path = Path::new("json");
+    if pointer.is_empty() {
+        return Ok(path);
+    }
+    let tokens = pointer
+        .strip_prefix('/')
+        .ok_or_else(|| Error::Patch(format!("Invalid JSON Pointer `{pointer}`")))?;
+
+    let mut current = Some(doc);
+    for token in tokens.split('/') {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        let key = match current {
+            Some(Value::Array(array)) if token == "-" => NamespaceKey::Array(array.len()),
+            Some(Value::Array(_)) => NamespaceKey::Array(
+                token
+                    .parse()
+                    .map_err(|_| Error::Patch(format!("Invalid array index `{token}`")))?,
+            ),
+            Some(Value::Object(_)) => NamespaceKey::Object(token),
+            _ => return Err(not_found(&path)),
+        };
+        current = match (current, &key) {
+            (Some(Value::Array(array)), NamespaceKey::Array(n)) => array.get(*n),
+            (Some(Value::Object(object)), NamespaceKey::Object(k)) => object.get(k),
+            _ => None,
+        };
+        path.push(key);
+    }
+
+    Ok(path)
+}
+
+fn get_mut<'v>(value: &'v mut Value, keys: &[NamespaceKey]) -> Option<&'v mut Value> {
+    keys.iter().try_fold(value, |current, key| match key {
+        NamespaceKey::Object(k) => current.get_mut(k.as_str()),
+        NamespaceKey::Array(n) => current.get_mut(n),
+    })
+}
+
+fn add(
+    doc: &mut Value,
+    path: &Path,
+    value: Value,
+    statements: &mut Vec<Statement>,
+) -> Result<(), Error> {
+    let Some(parent) = path.parent() else {
+        return replace(doc, path, value, statements);
+    };
+
+    let container = get_mut(doc, &parent.keys).ok_or_else(|| not_found(&parent))?;
+    match (container, path.keys.last()) {
+        (Value::Array(array), Some(NamespaceKey::Array(n))) if *n < array.len() => {
+            array.insert(*n, value);
+            let array = Value::Array(array.clone());
+            statements.push(Statement::Delete(parent.clone()));
+            statements.extend(Statements::new(parent, array));
+        }
+        (Value::Array(array), Some(NamespaceKey::Array(n))) if *n == array.len() => {
+            array.push(value.clone());
+            statements.extend(Statements::new(path.clone(), value));
+        }
+        (Value::Object(_), Some(NamespaceKey::Object(_))) => {
+            return replace(doc, path, value, statements);
+        }
+        _ => return Err(Error::Patch(format!("Cannot add `{path}`"))),
+    }
+
+    Ok(())
+}
+
+fn replace(
+    doc: &mut Value,
+    path: &Path,
+    value: Value,
+    statements: &mut Vec<Statement>,
+) -> Result<(), Error> {
+    let target = match path.keys.split_last() {
+        Some((NamespaceKey::Object(k), parents)) => get_mut(doc, parents)
+            .and_then(Value::as_object_mut)
+            .map(|object| object.entry(k.as_str()).or_insert(Value::Null)),
+        _ => get_mut(doc, &path.keys),
+    };
+    let target = target.ok_or_else(|| not_found(path))?;
+
+    let is_container = |v: &Value| v.is_array() || v.is_object();
+    if is_container(target) && is_container(&value) {
+        statements.push(Statement::Delete(path.clone()));
+    }
+    statements.extend(Statements::new(path.clone(), value.clone()));
+    *target = value;
+
+    Ok(())
+}
+
+fn remove(doc: &mut Value, path: &Path) -> Result<Value, Error> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| Error::Patch("Cannot remove the root".into()))?;
+    let removed = match (get_mut(doc, &parent.keys), path.keys.last()) {
+        (Some(Value::Object(object)), Some(NamespaceKey::Object(k))) => object.remove(k),
+        (Some(Value::Array(array)), Some(NamespaceKey::Array(n))) if *n < array.len() => {
+            Some(array.remove(*n))
+        }
+        _ => None,
+    };
+    removed.ok_or_else(|| not_found(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_diff_json_patch() {
+        let from = json!({ "a": 1, "b": [1, 2, 3], "c": { "d": 1 } });
+        let to = json!({ "a": 2, "b": [1], "c": { "e": 1 }, "f/g": null });
+        let patch = diff_json_patch(&from, &to);
+
+        assert_eq!(
+            serde_json::to_value(&patch).unwrap(),
+            json!([
+                { "op": "replace", "path": "/a", "value": 2 },
+                { "op": "remove", "path": "/b/2" },
+                { "op": "remove", "path": "/b/1" },
+                { "op": "remove", "path": "/c/d" },
+                { "op": "add", "path": "/c/e", "value": 1 },
+                { "op": "add", "path": "/f~1g", "value": null },
+            ])
+        );
+
+        let mut value = from.clone();
+        crate::apply(&mut value, json_patch_to_statements(&from, &patch).unwrap()).unwrap();
+        assert_eq!(value, to);
+    }
+
+    #[test]
+    fn test_diff_merge_patch() {
+        let from = json!({ "a": 1, "b": { "c": 1, "d": 2 }, "e": [1] });
+        let to = json!({ "a": 1, "b": { "c": 3 }, "e": [1, 2] });
+        assert_eq!(
+            diff_merge_patch(&from, &to),
+            json!({ "b": { "c": 3, "d": null }, "e": [1, 2] })
+        );
+    }
+
+    #[test]
+    fn test_json_patch_to_statements() {
+        let base = json!({ "a": [1, 2], "b": { "c": 1 } });
+        let patch: Vec<PatchOperation> = serde_json::from_value(json!([
+            { "op": "add", "path": "/a/1", "value": 9 },
+            { "op": "replace", "path": "/b", "value": { "d": 1 } },
+            { "op": "move", "from": "/b/d", "path": "/e" },
+            { "op": "test", "path": "/e", "value": 1 },
+        ]))
+        .unwrap();
+
+        let statements = json_patch_to_statements(&base, &patch).unwrap();
+        let text: Vec<_> = statements.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            text,
+            [
+                "delete json.a;",
+                "json.a = [];",
+                "json.a[0] = 1;",
+                "json.a[1] = 9;",
+                "json.a[2] = 2;",
+                "delete json.b;",
+                "json.b = {};",
+                "json.b.d = 1;",
+                "delete json.b.d;",
+                "json.e = 1;",
+            ]
+        );
+
+        let mut value = base.clone();
+        crate::apply(&mut value, statements).unwrap();
+        assert_eq!(value, json!({ "a": [1, 9, 2], "b": {}, "e": 1 }));
+    }
+
+    #[test]
+    fn test_json_patch_errors() {
+        let base = json!({ "a": 1 });
+        let patch = [PatchOperation::Test {
+            path: "/a".into(),
+            value: json!(2),
+        }];
+        assert!(matches!(
+            json_patch_to_statements(&base, &patch),
+            Err(Error::Patch(_))
+        ));
+
+        let patch = [PatchOperation::Remove { path: "/b".into() }];
+        assert!(json_patch_to_statements(&base, &patch).is_err());
+    }
+}
@@ -0,0 +1,313 @@
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Display, Write as _},
+    str::CharIndices,
+};
+
+use crate::{
+    ser::{is_identifier, NamespaceKey},
+    write::write_json_string,
+    Error,
+};
+
+/// The left-hand side of a gron statement, e.g. `json.a[0]["b-c"]`.
+///
+/// Object keys are stored unescaped; they are quoted when the path is displayed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Path {
+    pub root: String,
+    pub keys: Vec<NamespaceKey>,
+}
+
+impl Path {
+    pub fn new(root: impl Into<String>) -> Path {
+        Path {
+            root: root.into(),
+            keys: vec![],
+        }
+    }
+
+    pub fn with_keys(root: impl Into<String>, keys: Vec<NamespaceKey>) -> Path {
+        Path {
+            root: root.into(),
+            keys,
+        }
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn push(&mut self, key: NamespaceKey) {
+        self.keys.push(key);
+    }
+
+    pub fn pop(&mut self) -> Option<NamespaceKey> {
+        self.keys.pop()
+    }
+
+    /// Returns the path of the parent container, or `None` for the root.
+    pub fn parent(&self) -> Option<Path> {
+        let (_, keys) = self.keys.split_last()?;
+        Some(Path::with_keys(self.root.clone(), keys.to_vec()))
+    }
+
+    /// Renders the path as an RFC 6901 JSON Pointer, e.g. `/a/0/b~1c`.
+    pub fn to_json_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for key in self.keys.iter() {
+            pointer.push('/');
+            match key {
+                NamespaceKey::Array(n) => pointer.push_str(&n.to_string()),
+                NamespaceKey::Object(k) => {
+                    pointer.push_str(&k.replace('~', "~0").replace('/', "~1"))
+                }
+            }
+        }
+        pointer
+    }
+
+    /// Parses an RFC 6901 JSON Pointer.
+    ///
+    /// A pointer does not say whether a numeric token is an array index or an object key, so
+    /// tokens such as `0` and `12` become [`NamespaceKey::Array`] and all others become
+    /// [`NamespaceKey::Object`].
+    pub fn from_json_pointer(root: impl Into<String>, pointer: &str) -> Result<Path, Error> {
+        let mut path = Path::new(root);
+        if pointer.is_empty() {
+            return Ok(path);
+        }
+        let tokens = pointer
+            .strip_prefix('/')
+            .ok_or_else(|| invalid_path(pointer, "a JSON Pointer must start with `/`"))?;
+
+        for token in tokens.split('/') {
+            let key = match parse_index(token) {
+                Some(n) => NamespaceKey::Array(n),
+                None => NamespaceKey::Object(token.replace("~1", "/").replace("~0", "~")),
+            };
+            path.push(key);
+        }
+
+        Ok(path)
+    }
+
+    /// Renders the path as an RFC 9535 normalized JSONPath, e.g. `$['a'][0]['b-c']`.
+    pub fn to_json_path(&self) -> String {
+        let mut json_path = String::from("$");
+        for key in self.keys.iter() {
+            match key {
+                NamespaceKey::Array(n) => write!(json_path, "[{n}]").unwrap(),
+                NamespaceKey::Object(k) => {
+                    json_path.push_str("['");
+                    for c in k.chars() {
+                        match c {
+                            '\'' => json_path.push_str("\\'"),
+                            '\\' => json_path.push_str("\\\\"),
+                            '\u{8}' => json_path.push_str("\\b"),
+                            '\u{c}' => json_path.push_str("\\f"),
+                            '\n' => json_path.push_str("\\n"),
+                            '\r' => json_path.push_str("\\r"),
+                            '\t' => json_path.push_str("\\t"),
+                            '\0'..='\u{1f}' => write!(json_path, "\\u{:04x}", c as u32).unwrap(),
+                            c => json_path.push(c),
+                        }
+                    }
+                    json_path.push_str("']");
+                }
+            }
+        }
+        json_path
+    }
+
+    /// Parses a JSONPath made of single names and indexes, such as `$['a'][0]` or `$.a[0]`.
+    ///
+    /// Names may be quoted with either single or double quotes. Wildcards, slices and
+    /// filters select more than one node and are rejected.
+    pub fn from_json_path(root: impl Into<String>, json_path: &str) -> Result<Path, Error> {
+        let invalid = |message: &str| invalid_path(json_path, message);
+
+        let mut path = Path::new(root);
+        let mut rest = json_path
+            .strip_prefix('$')
+            .ok_or_else(|| invalid("a JSONPath must start with `$`"))?;
+
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('.') {
+                let len = after
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                if len == 0 || after.starts_with(|c: char| c.is_ascii_digit()) {
+                    return Err(invalid("expected a member name after `.`"));
+                }
+                path.push(NamespaceKey::Object(after[..len].to_string()));
+                rest = &after[len..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let (key, after) = match after.chars().next() {
+                    Some(quote @ ('\'' | '"')) => {
+                        let (name, after) = parse_quoted(&after[1..], quote)
+                            .ok_or_else(|| invalid("unterminated or invalid name"))?;
+                        (NamespaceKey::Object(name), after)
+                    }
+                    _ => {
+                        let end = after.find(']').unwrap_or(after.len());
+                        let n = parse_index(&after[..end])
+                            .ok_or_else(|| invalid("expected a quoted name or an array index"))?;
+                        (NamespaceKey::Array(n), &after[end..])
+                    }
+                };
+                rest = after
+                    .strip_prefix(']')
+                    .ok_or_else(|| invalid("expected `]`"))?;
+                path.push(key);
+            } else {
+                return Err(invalid("expected `.` or `[`"));
+            }
+        }
+
+        Ok(path)
+    }
+}
+
+fn invalid_path(path: &str, message: &str) -> Error {
+    Error::InvalidPath {
+        path: path.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// Parses an array index without leading zeros or a sign.
+fn parse_index(s: &str) -> Option<usize> {
+    let valid = s == "0" || (!s.starts_with('0') && !s.is_empty());
+    if valid && s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Unescapes a quoted JSONPath name up to the closing `quote`, returning the name and the
+/// input after the quote.
+fn parse_quoted(s: &str, quote: char) -> Option<(String, &str)> {
+    let mut name = String::new();
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c == quote => return Some((name, &s[i + 1..])),
+            '\\' => {
+                let c = match chars.next()?.1 {
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => {
+                        let high = parse_hex4(&mut chars)?;
+                        let units = if (0xd800..0xdc00).contains(&high) {
+                            chars.next().filter(|&(_, c)| c == '\\')?;
+                            chars.next().filter(|&(_, c)| c == 'u')?;
+                            vec![high, parse_hex4(&mut chars)?]
+                        } else {
+                            vec![high]
+                        };
+                        char::decode_utf16(units).next()?.ok()?
+                    }
+                    c @ ('\'' | '"' | '/' | '\\') => c,
+                    _ => return None,
+                };
+                name.push(c);
+            }
+            c => name.push(c),
+        }
+    }
+    None
+}
+
+fn parse_hex4(chars: &mut CharIndices<'_>) -> Option<u16> {
+    let digits: String = chars.take(4).map(|(_, c)| c).collect();
+    if digits.len() == 4 {
+        u16::from_str_radix(&digits, 16).ok()
+    } else {
+        None
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.root)?;
+        for key in self.keys.iter() {
+            match key {
+                NamespaceKey::Array(n) => write!(f, "[{n}]")?,
+                NamespaceKey::Object(k) => {
+                    if is_identifier(k) {
+                        write!(f, ".{k}")?;
+                    } else {
+                        f.write_char('[')?;
+                        write_json_string(k, |s| f.write_str(s))?;
+                        f.write_char(']')?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path() -> Path {
+        Path::with_keys(
+            "json",
+            vec![
+                NamespaceKey::Object("a".into()),
+                NamespaceKey::Array(0),
+                NamespaceKey::Object("b/c~d".into()),
+                NamespaceKey::Object("it's\n".into()),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_json_pointer() {
+        let pointer = path().to_json_pointer();
+        assert_eq!(pointer, "/a/0/b~1c~0d/it's\n");
+        assert_eq!(Path::from_json_pointer("json", &pointer).unwrap(), path());
+        assert_eq!(
+            Path::from_json_pointer("json", "").unwrap(),
+            Path::new("json")
+        );
+        assert_eq!(
+            Path::from_json_pointer("json", "/01/").unwrap().keys,
+            [
+                NamespaceKey::Object("01".into()),
+                NamespaceKey::Object("".into())
+            ]
+        );
+        assert!(Path::from_json_pointer("json", "a").is_err());
+    }
+
+    #[test]
+    fn test_json_path() {
+        let json_path = path().to_json_path();
+        assert_eq!(json_path, r"$['a'][0]['b/c~d']['it\'s\n']");
+        assert_eq!(Path::from_json_path("json", &json_path).unwrap(), path());
+        assert_eq!(
+            Path::from_json_path("json", r#"$.a[0]["b/c~d"]["it's\u000a"]"#).unwrap(),
+            path()
+        );
+        assert_eq!(
+            Path::from_json_path("json", r"$['😀']").unwrap().keys,
+            [NamespaceKey::Object("😀".into())]
+        );
+        for invalid in ["a", "$[*]", "$['a'", "$[01]", "$.", "$['\\x']"] {
+            assert!(Path::from_json_path("json", invalid).is_err(), "{invalid}");
+        }
+    }
+}
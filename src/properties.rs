@@ -0,0 +1,354 @@
+//! Java `.properties` output and input, e.g. `database.hosts[0]=db1`.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{Display, Write as _};
+
+use crate::{
+    apply::unflatten,
+    ser::{Formatter, NamespaceKey, Serializer},
+    string_value::StringValue,
+    write::{write_json_string, FmtWriter},
+    Error, Options, Path, Write,
+};
+
+/// Writes one `key=value` line per leaf.
+///
+/// Object keys are joined with `.` and array indexes are written as `[0]`. Keys containing
+/// `.`, `[`, `]` or `"` are written as a quoted string in brackets, e.g. `a["b.c"]`. Keys and
+/// values are escaped as `java.util.Properties` does, including `\uXXXX` escapes for
+/// non-ASCII characters, so files can be read as ISO-8859-1.
+#[derive(Debug, Default)]
+pub struct PropertiesFormatter;
+
+impl<W: Write> Formatter<W> for PropertiesFormatter {
+    fn write_root(&mut self, _key: &mut String, _ns_root: &str) {}
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        match ns {
+            NamespaceKey::Array(n) => write!(key, "[{n}]").unwrap(),
+            NamespaceKey::Object(k) if !k.is_empty() && !k.contains(['.', '[', ']', '"']) => {
+                if !key.is_empty() {
+                    key.push('.');
+                }
+                escape(key, k, true);
+            }
+            NamespaceKey::Object(k) => {
+                let mut quoted = String::new();
+                write_json_string(k, |s| quoted.write_str(s)).unwrap();
+                key.push('[');
+                escape(key, &quoted, true);
+                key.push(']');
+            }
+        }
+    }
+
+    fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("=")
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("\n")
+    }
+
+    fn write_null(&mut self, _writer: &mut W) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        let mut escaped = String::with_capacity(value.len());
+        escape(&mut escaped, value, false);
+        writer.write_str(&escaped)
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("[]")
+    }
+
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str("{}")
+    }
+}
+
+/// Escapes `s` like `java.util.Properties::store`. Spaces are only escaped in keys and at the
+/// start of values.
+fn escape(out: &mut String, s: &str, key: bool) {
+    for (i, c) in s.chars().enumerate() {
+        match c {
+            ' ' if key || i == 0 => out.push_str("\\ "),
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\u{c}' => out.push_str("\\f"),
+            '=' | ':' | '#' | '!' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            c => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    write!(out, "\\u{unit:04X}").unwrap();
+                }
+            }
+        }
+    }
+}
+
+pub fn to_properties_string(value: &impl Serialize) -> Result<String, Error> {
+    let mut s = String::new();
+    to_properties_writer(value, FmtWriter(&mut s))?;
+    Ok(s)
+}
+
+/// Writes `value` in `.properties` syntax.
+///
+/// ```
+/// use serde_json::json;
+///
+/// let value = json!({ "database": { "hosts": ["db1"], "url": "jdbc:x" } });
+/// assert_eq!(
+///     serde_gron::to_properties_string(&value).unwrap(),
+///     "database.hosts[0]=db1\ndatabase.url=jdbc\\:x\n"
+/// );
+/// ```
+pub fn to_properties_writer(value: &impl Serialize, writer: impl Write) -> Result<(), Error> {
+    let options = Options {
+        root_name: String::new(),
+        leaf_only: true,
+        ..Options::default()
+    };
+    let mut ser = Serializer::with_options(writer, PropertiesFormatter, &options);
+    value.serialize(&mut ser)?;
+    ser.end_document()
+}
+
+/// Deserializes a `T` from a `.properties` file.
+///
+/// Comments, line continuations and the `=`, `:` and whitespace separators are handled as in
+/// `java.util.Properties::load`. Keys are read as written by [`to_properties_string`], so
+/// `a.b[0]` is an array inside an object. Values are parsed into the type each field expects;
+/// deserializing into a `serde_json::Value` gives strings.
+pub fn from_properties_str<T: DeserializeOwned>(s: &str) -> Result<T, Error> {
+    let mut pairs = Vec::new();
+    let mut lines = s.lines().enumerate();
+    while let Some((i, line)) = lines.next() {
+        let mut text = String::from(trim_start(line));
+        if text.is_empty() || text.starts_with(['#', '!']) {
+            continue;
+        }
+        while is_continued(&text) {
+            text.pop();
+            match lines.next() {
+                Some((_, next)) => text.push_str(trim_start(next)),
+                None => break,
+            }
+        }
+
+        let syntax = |message| Error::Syntax {
+            line: i + 1,
+            message,
+        };
+        let (key, value) = split_entry(&text);
+        let path = parse_key(&unescape(key).map_err(syntax)?).map_err(syntax)?;
+        pairs.push((path, Value::String(unescape(value).map_err(syntax)?)));
+    }
+
+    T::deserialize(StringValue(&unflatten(pairs)?))
+}
+
+fn trim_start(line: &str) -> &str {
+    line.trim_start_matches([' ', '\t', '\u{c}'])
+}
+
+/// Returns whether the line ends with an odd number of backslashes.
+fn is_continued(line: &str) -> bool {
+    line.bytes().rev().take_while(|&b| b == b'\\').count() % 2 == 1
+}
+
+/// Splits a logical line into its still escaped key and value.
+fn split_entry(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    let end = line
+        .char_indices()
+        .find(|&(_, c)| {
+            let found = !escaped && matches!(c, '=' | ':' | ' ' | '\t' | '\u{c}');
+            escaped = !escaped && c == '\\';
+            found
+        })
+        .map_or(line.len(), |(i, _)| i);
+
+    let value = trim_start(&line[end..]);
+    let value = value.strip_prefix(['=', ':']).map_or(value, trim_start);
+    (&line[..end], value)
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    let mut units = Vec::new();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('f') => out.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let unit = u16::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == 4)
+                    .ok_or_else(|| format!("Invalid unicode escape `\\u{hex}`"))?;
+                units.push(unit);
+                // A high surrogate must be followed by the escape of its low surrogate.
+                if !(0xd800..0xdc00).contains(&unit) {
+                    for c in char::decode_utf16(units.drain(..)) {
+                        out.push(c.map_err(|_| "Invalid surrogate pair".to_string())?);
+                    }
+                }
+            }
+            Some(c) => out.push(c),
+            None => {}
+        }
+    }
+    if !units.is_empty() {
+        return Err("Unpaired surrogate".to_string());
+    }
+
+    Ok(out)
+}
+
+/// Parses a key such as `a.b[0]["c.d"]`.
+fn parse_key(key: &str) -> Result<Path, String> {
+    let mut path = Path::new("");
+    let mut rest = key;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            if after.starts_with('"') {
+                let mut stream = serde_json::Deserializer::from_str(after).into_iter::<String>();
+                let k = match stream.next() {
+                    Some(Ok(k)) => k,
+                    _ => return Err(format!("Invalid quoted key in `{key}`")),
+                };
+                rest = &after[stream.byte_offset()..];
+                path.push(NamespaceKey::Object(k));
+            } else {
+                let end = after.find(']').unwrap_or(after.len());
+                let n = after[..end]
+                    .parse()
+                    .map_err(|_| format!("Invalid array index in `{key}`"))?;
+                rest = &after[end..];
+                path.push(NamespaceKey::Array(n));
+            }
+            rest = rest
+                .strip_prefix(']')
+                .ok_or_else(|| format!("Expected `]` in `{key}`"))?;
+        } else {
+            let segment = if path.is_root() {
+                rest
+            } else {
+                rest.strip_prefix('.')
+                    .ok_or_else(|| format!("Expected `.` or `[` in `{key}`"))?
+            };
+            let end = segment.find(['.', '[']).unwrap_or(segment.len());
+            if end == 0 {
+                return Err(format!("Empty key segment in `{key}`"));
+            }
+            path.push(NamespaceKey::Object(segment[..end].into()));
+            rest = &segment[end..];
+        }
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_properties_round_trip() {
+        #[derive(Debug, Deserialize, Serialize, PartialEq)]
+        struct Config {
+            database: Database,
+            #[serde(rename = "log.level")]
+            log_level: String,
+            flags: Vec<bool>,
+        }
+
+        #[derive(Debug, Deserialize, Serialize, PartialEq)]
+        struct Database {
+            hosts: Vec<String>,
+            port: u16,
+            banner: String,
+        }
+
+        let config = Config {
+            database: Database {
+                hosts: vec!["db1".into(), "db2".into()],
+                port: 5432,
+                banner: " Hi = #1\n日本".into(),
+            },
+            log_level: "debug".into(),
+            flags: vec![],
+        };
+        let properties = to_properties_string(&config).unwrap();
+        assert_eq!(
+            properties,
+            r#"database.hosts[0]=db1
+database.hosts[1]=db2
+database.port=5432
+database.banner=\ Hi \= \#1\n\u65E5\u672C
+["log.level"]=debug
+flags=[]
+"#
+        );
+        assert_eq!(from_properties_str::<Config>(&properties).unwrap(), config);
+    }
+
+    #[test]
+    fn test_from_properties_str() {
+        let input = r"# comment
+  ! another
+a.b : 1
+a.c   two words \
+      continued
+a.d=
+eé😀=y
+";
+        let value: Value = from_properties_str(input).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "a": { "b": "1", "c": "two words continued", "d": "" },
+                "eé😀": "y"
+            })
+        );
+    }
+}
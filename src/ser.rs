@@ -1,33 +1,24 @@
 use bool_ext::BoolExt;
-use once_cell::sync::Lazy;
-use regex::Regex;
 use serde::{ser, Serialize};
-use std::{fmt::Display, io};
-
-#[derive(thiserror::Error, Debug)]
-pub enum Error {
-    #[error("Invalid root name")]
-    InvalidRootName,
-
-    #[error("Reached end of file")]
-    Eof,
-    #[error(transparent)]
-    Serialize(serde_json::Error),
-    #[error(transparent)]
-    Io(io::Error),
-
-    #[error("Error: {0}")]
-    Custom(String),
-}
-
-impl ser::Error for Error {
-    fn custom<T>(msg: T) -> Self
-    where
-        T: std::fmt::Display,
-    {
-        Self::Custom(msg.to_string())
-    }
-}
+use serde_json::Value;
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{Display, Write as _};
+
+use crate::{
+    align::{Aligned, Alignment},
+    color::{ColorTheme, Style},
+    transcode::Transcoder,
+    write::{write_json_string, FmtWriter},
+    Error, Write,
+};
+
+/// The struct name `serde_json` uses to serialize `RawValue`.
+const RAW_VALUE_TOKEN: &str = "$serde_json::private::RawValue";
 
 pub fn to_string(value: &impl Serialize) -> Result<String, Error> {
     to_string_with(value, "json", FormatType::Regular)
@@ -42,56 +33,378 @@ pub fn to_string_with(
     root_name: impl Into<String>,
     format_type: FormatType,
 ) -> Result<String, Error> {
-    let mut writer = vec![];
-    to_writer_with(value, &mut writer, root_name, format_type)?;
-    Ok(String::from_utf8(writer).unwrap())
+    to_string_with_options(value, &Options::new(root_name, format_type))
+}
+
+pub fn to_string_with_options(value: &impl Serialize, options: &Options) -> Result<String, Error> {
+    let mut s = String::new();
+    to_writer_with_options(value, FmtWriter(&mut s), options)?;
+    Ok(s)
 }
 
-pub fn to_writer(value: &impl Serialize, writer: &mut impl io::Write) -> Result<(), Error> {
+pub fn to_writer(value: &impl Serialize, writer: impl Write) -> Result<(), Error> {
     to_writer_with(value, writer, "json", FormatType::Regular)
 }
 
-pub fn to_colored_writer(value: &impl Serialize, writer: &mut impl io::Write) -> Result<(), Error> {
+pub fn to_colored_writer(value: &impl Serialize, writer: impl Write) -> Result<(), Error> {
     to_writer_with(value, writer, "json", FormatType::Color)
 }
 
 pub fn to_writer_with(
     value: &impl Serialize,
-    writer: &mut impl io::Write,
+    writer: impl Write,
     root_name: impl Into<String>,
     format_type: FormatType,
 ) -> Result<(), Error> {
-    match format_type {
-        FormatType::Regular => {
-            let mut ser = Serializer::<_, RegularFormatter>::new_with_root_name(writer, root_name);
-            value.serialize(&mut ser)?;
+    to_writer_with_options(value, writer, &Options::new(root_name, format_type))
+}
+
+/// Like [`to_writer_with`], but takes every output setting from `options`.
+///
+/// ```
+/// use serde_gron::{LineSyntax, LineTerminator, Options};
+///
+/// let options = Options {
+///     syntax: LineSyntax {
+///         spaced_delimiter: false,
+///         semicolon: false,
+///         terminator: LineTerminator::Nul,
+///     },
+///     ..Options::default()
+/// };
+/// let gron = serde_gron::to_string_with_options(&[1], &options);
+/// assert_eq!(gron.unwrap(), "json=[]\0json[0]=1\0");
+/// ```
+pub fn to_writer_with_options(
+    value: &impl Serialize,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    if options.collapses() {
+        // Collapsing depends on the size of each subtree, so build the whole value first.
+        let value = serde_json::to_value(value).map_err(Error::Serialize)?;
+        return value_to_writer_with_options(&value, writer, options);
+    }
+
+    write_with_options(Serialized(value), writer, options)
+}
+
+/// Like [`to_string`], but walks the `serde_json::Value` directly, which is faster.
+pub fn value_to_string(value: &Value) -> Result<String, Error> {
+    let mut s = String::new();
+    value_to_writer(value, FmtWriter(&mut s))?;
+    Ok(s)
+}
+
+/// Like [`to_writer`], but walks the `serde_json::Value` directly, which is faster.
+pub fn value_to_writer(value: &Value, writer: impl Write) -> Result<(), Error> {
+    value_to_writer_with(value, writer, "json", FormatType::Regular)
+}
+
+/// Like [`to_writer_with`], but walks the `serde_json::Value` directly, which is faster.
+pub fn value_to_writer_with(
+    value: &Value,
+    writer: impl Write,
+    root_name: impl Into<String>,
+    format_type: FormatType,
+) -> Result<(), Error> {
+    value_to_writer_with_options(value, writer, &Options::new(root_name, format_type))
+}
+
+/// Like [`to_writer_with_options`], but walks the `serde_json::Value` directly, which is
+/// faster.
+pub fn value_to_writer_with_options(
+    value: &Value,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    write_with_options(JsonValue(value), writer, options)
+}
+
+/// A value the `*_with_options` functions write, whichever formatter they pick.
+trait Input {
+    fn write_to<W: Write, F: Formatter<W>>(self, ser: &mut Serializer<W, F>) -> Result<(), Error>;
+}
+
+struct Serialized<'a, T: ?Sized>(&'a T);
+
+impl<T: Serialize + ?Sized> Input for Serialized<'_, T> {
+    fn write_to<W: Write, F: Formatter<W>>(self, ser: &mut Serializer<W, F>) -> Result<(), Error> {
+        self.0.serialize(ser)
+    }
+}
+
+struct JsonValue<'a>(&'a Value);
+
+impl Input for JsonValue<'_> {
+    fn write_to<W: Write, F: Formatter<W>>(self, ser: &mut Serializer<W, F>) -> Result<(), Error> {
+        ser.serialize_json_value(self.0)
+    }
+}
+
+fn write_with_options(
+    input: impl Input,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    if options.format_type.is_colored(writer.is_terminal()) {
+        let formatter = ColorFormatter::new(options.syntax, options.theme);
+        write_formatted(input, writer, formatter, options)
+    } else {
+        let formatter = RegularFormatter::with_syntax(options.syntax);
+        write_formatted(input, writer, formatter, options)
+    }
+}
+
+fn write_formatted<W, F>(
+    input: impl Input,
+    writer: W,
+    formatter: F,
+    options: &Options,
+) -> Result<(), Error>
+where
+    W: Write,
+    F: Formatter<W> + Formatter<FmtWriter<String>>,
+{
+    match options.align {
+        Some(alignment) => {
+            let formatter = Aligned::new(formatter, alignment);
+            let mut ser = Serializer::with_options(writer, formatter, options);
+            input.write_to(&mut ser)?;
+            ser.end_document()
         }
-        FormatType::Color => {
-            let mut ser = Serializer::<_, ColorFormatter>::new_with_root_name(writer, root_name);
-            value.serialize(&mut ser)?;
+        None => {
+            let mut ser = Serializer::with_options(writer, formatter, options);
+            input.write_to(&mut ser)?;
+            ser.end_document()
         }
-    };
-
-    Ok(())
+    }
 }
 
+/// Output settings for the `*_with_options` functions.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The name statements start with. Defaults to `json`.
+    pub root_name: String,
+    pub format_type: FormatType,
+    /// The colors of [`FormatType::Color`] output.
+    pub theme: ColorTheme,
+    pub syntax: LineSyntax,
+    /// Omits the initialisation statements (`json.a = {};`) of non-empty containers.
+    ///
+    /// Empty containers are still written, since they could not be represented otherwise.
+    /// Parsers infer the other containers from the paths below them.
+    pub leaf_only: bool,
+    /// Writes containers at this depth or deeper as a single statement with a compact JSON
+    /// value, e.g. `json.a.b = {"c":[1,2]};`. The root is at depth 0.
+    pub collapse_depth: Option<usize>,
+    /// Writes containers with fewer than this many leaves as a single statement with a
+    /// compact JSON value. Empty containers count as leaves.
+    pub collapse_leaves: Option<usize>,
+    /// Pads keys so the delimiters of each group of statements line up.
+    pub align: Option<Alignment>,
+}
+
+impl Options {
+    pub fn new(root_name: impl Into<String>, format_type: FormatType) -> Options {
+        Options {
+            root_name: root_name.into(),
+            format_type,
+            theme: ColorTheme::default(),
+            syntax: LineSyntax::default(),
+            leaf_only: false,
+            collapse_depth: None,
+            collapse_leaves: None,
+            align: None,
+        }
+    }
+
+    pub(crate) fn collapses(&self) -> bool {
+        self.collapse_depth.is_some() || self.collapse_leaves.is_some()
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options::new("json", FormatType::Regular)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub enum FormatType {
     /// Non colored output
+    #[default]
     Regular,
     /// Colored output
     Color,
+    /// Colored output if the writer is a terminal, unless `NO_COLOR` is set.
+    /// `CLICOLOR_FORCE` forces colors even when it is not.
+    ///
+    /// Strings and other [`Write`]s are not terminals unless they say so; wrap a
+    /// `std::io::Write` in [`Terminal`](crate::Terminal) to mark it as one.
+    #[cfg(feature = "std")]
+    Auto,
 }
 
-#[derive(Debug)]
+impl FormatType {
+    /// Chooses [`FormatType::Color`] or [`FormatType::Regular`] for output to `stream`.
+    ///
+    /// A non-empty `NO_COLOR` disables colors, and otherwise a `CLICOLOR_FORCE` other than
+    /// `0` enables them; without either, colors are used if `stream` is a terminal.
+    #[cfg(feature = "std")]
+    pub fn detect(stream: &impl std::io::IsTerminal) -> FormatType {
+        if FormatType::Auto.is_colored(stream.is_terminal()) {
+            FormatType::Color
+        } else {
+            FormatType::Regular
+        }
+    }
+
+    /// Whether output to a writer that is a terminal or not is colored.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    pub(crate) fn is_colored(&self, is_terminal: bool) -> bool {
+        match self {
+            FormatType::Regular => false,
+            FormatType::Color => true,
+            #[cfg(feature = "std")]
+            FormatType::Auto => {
+                let var = |name| std::env::var_os(name).filter(|v| !v.is_empty());
+                if var("NO_COLOR").is_some() {
+                    false
+                } else if var("CLICOLOR_FORCE").is_some_and(|v| v != "0") {
+                    true
+                } else {
+                    is_terminal
+                }
+            }
+        }
+    }
+}
+
+/// How the key and value of a statement are separated, and how statements end.
+///
+/// The default, `json.a = 1;` followed by `\n`, matches the original `gron`. The parser
+/// accepts every variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LineSyntax {
+    /// Writes ` = ` between the key and the value instead of `=`.
+    pub spaced_delimiter: bool,
+    /// Ends each statement with `;` before the terminator.
+    pub semicolon: bool,
+    pub terminator: LineTerminator,
+}
+
+impl LineSyntax {
+    pub fn delimiter(&self) -> &'static str {
+        if self.spaced_delimiter {
+            " = "
+        } else {
+            "="
+        }
+    }
+
+    pub fn end_of_line(&self) -> &'static str {
+        match (self.semicolon, self.terminator) {
+            (true, LineTerminator::Lf) => ";\n",
+            (true, LineTerminator::CrLf) => ";\r\n",
+            (true, LineTerminator::Nul) => ";\0",
+            (false, LineTerminator::Lf) => "\n",
+            (false, LineTerminator::CrLf) => "\r\n",
+            (false, LineTerminator::Nul) => "\0",
+        }
+    }
+}
+
+impl Default for LineSyntax {
+    fn default() -> Self {
+        LineSyntax {
+            spaced_delimiter: true,
+            semicolon: true,
+            terminator: LineTerminator::Lf,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineTerminator {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+    /// `\0`, for tools such as `xargs -0`
+    Nul,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum NamespaceKey {
     Array(usize),
     Object(String),
 }
 
-pub trait Formatter<W: io::Write> {
-    fn write_key(&self, wriiter: &mut W, ns_root: &str, nss: &[NamespaceKey]) -> Result<(), Error>;
-    fn write_key_value_delimiter(&self, wriiter: &mut W) -> Result<(), Error>;
+/// Renders the parts of each statement.
+///
+/// For every statement the serializer calls `begin_statement`, `write_key`,
+/// `write_key_value_delimiter`, one of the value methods, `write_end_of_line` and
+/// `end_statement`, in that order. `begin_document` comes before the first statement and
+/// `end_document` after the last, when the serializer's
+/// [`end_document`](Serializer::end_document) is called. Every method takes `&mut self`, so a
+/// formatter can keep state across them, e.g. to buffer a statement or count lines.
+pub trait Formatter<W: Write> {
+    /// Renders the root name at the start of the key buffer.
+    fn write_root(&mut self, key: &mut String, ns_root: &str);
+    /// Appends a single path segment to the key buffer.
+    ///
+    /// The serializer calls this once when it enters a container member and truncates the
+    /// buffer again when it leaves, so each segment is rendered only once however many
+    /// statements are written below it.
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey);
+
+    fn begin_document(&mut self, _writer: &mut W) -> Result<(), Error> {
+        Ok(())
+    }
+    fn end_document(&mut self, _writer: &mut W) -> Result<(), Error> {
+        Ok(())
+    }
+    fn begin_statement(&mut self, _writer: &mut W) -> Result<(), Error> {
+        Ok(())
+    }
+    fn end_statement(&mut self, _writer: &mut W) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Writes the key rendered by `write_root` and `write_key_segment`.
+    fn write_key(&mut self, writer: &mut W, key: &str) -> Result<(), Error> {
+        writer.write_str(key)
+    }
+    fn write_key_value_delimiter(&mut self, wriiter: &mut W) -> Result<(), Error>;
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error>;
+
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error>;
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error>;
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error>;
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error>;
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error>;
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error>;
+    /// Writes a collapsed container as compact JSON.
+    fn write_json(&mut self, writer: &mut W, value: &Value) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+}
+
+/// The `Formatter` trait of serde-gron 0.1, whose methods take `&self`, write to
+/// [`std::io::Write`] and render the whole key of every statement in `write_key`.
+///
+/// Formatters written for 0.1 keep their code: implement this trait instead of [`Formatter`]
+/// and wrap the formatter in [`Stateless`] to use it.
+#[cfg(feature = "std")]
+pub trait StatelessFormatter<W: std::io::Write> {
+    fn write_key(&self, writer: &mut W, ns_root: &str, nss: &[NamespaceKey]) -> Result<(), Error>;
+    fn write_key_value_delimiter(&self, writer: &mut W) -> Result<(), Error>;
     fn write_end_of_line(&self, writer: &mut W) -> Result<(), Error>;
 
     fn write_null(&self, writer: &mut W) -> Result<(), Error>;
@@ -102,14 +415,200 @@ pub trait Formatter<W: io::Write> {
     fn write_init_object(&self, writer: &mut W) -> Result<(), Error>;
 }
 
+/// Adapts a [`StatelessFormatter`] to [`Formatter`], passing it the root name and the path
+/// segments of each statement.
+///
+/// ```
+/// use serde_gron::{Error, NamespaceKey, Serializer, Stateless, StatelessFormatter};
+/// use std::io::Write;
+///
+/// #[derive(Default)]
+/// struct Upper;
+///
+/// impl<W: Write> StatelessFormatter<W> for Upper {
+///     fn write_key(
+///         &self,
+///         writer: &mut W,
+///         ns_root: &str,
+///         nss: &[NamespaceKey],
+///     ) -> Result<(), Error> {
+///         write!(writer, "{}", ns_root.to_uppercase()).map_err(Error::Io)?;
+///         for ns in nss {
+///             if let NamespaceKey::Object(k) = ns {
+///                 write!(writer, ".{}", k.to_uppercase()).map_err(Error::Io)?;
+///             }
+///         }
+///         Ok(())
+///     }
+///     fn write_key_value_delimiter(&self, writer: &mut W) -> Result<(), Error> {
+///         writer.write_all(b" = ").map_err(Error::Io)
+///     }
+///     fn write_end_of_line(&self, writer: &mut W) -> Result<(), Error> {
+///         writer.write_all(b"\n").map_err(Error::Io)
+///     }
+///     fn write_null(&self, writer: &mut W) -> Result<(), Error> {
+///         writer.write_all(b"null").map_err(Error::Io)
+///     }
+///     fn write_bool(&self, writer: &mut W, value: bool) -> Result<(), Error> {
+///         write!(writer, "{value}").map_err(Error::Io)
+///     }
+///     fn write_number<N: num::Num + std::fmt::Display>(
+///         &self,
+///         writer: &mut W,
+///         value: N,
+///     ) -> Result<(), Error> {
+///         write!(writer, "{value}").map_err(Error::Io)
+///     }
+///     fn write_string(&self, writer: &mut W, value: &str) -> Result<(), Error> {
+///         write!(writer, "{value:?}").map_err(Error::Io)
+///     }
+///     fn write_init_array(&self, writer: &mut W) -> Result<(), Error> {
+///         writer.write_all(b"[]").map_err(Error::Io)
+///     }
+///     fn write_init_object(&self, writer: &mut W) -> Result<(), Error> {
+///         writer.write_all(b"{}").map_err(Error::Io)
+///     }
+/// }
+///
+/// let mut out = vec![];
+/// let mut ser = Serializer::with_formatter(&mut out, Stateless::new(Upper), "json");
+/// serde::Serialize::serialize(&serde_json::json!({ "a": { "b": 1 } }), &mut ser).unwrap();
+/// ser.end_document().unwrap();
+/// drop(ser);
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     "JSON = {}\nJSON.A = {}\nJSON.A.B = 1\n"
+/// );
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct Stateless<F> {
+    inner: F,
+    path: KeyPath,
+}
+
+#[cfg(feature = "std")]
+impl<F> Stateless<F> {
+    pub fn new(inner: F) -> Self {
+        Stateless {
+            inner,
+            path: KeyPath::default(),
+        }
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write, F: StatelessFormatter<W>> Formatter<W> for Stateless<F> {
+    fn write_root(&mut self, _key: &mut String, ns_root: &str) {
+        self.path.root = ns_root.to_string();
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        self.path.push(key, ns);
+    }
+
+    fn write_key(&mut self, writer: &mut W, key: &str) -> Result<(), Error> {
+        let (root, keys) = self.path.get(key);
+        self.inner.write_key(writer, root, keys)
+    }
+
+    fn write_key_value_delimiter(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.inner.write_key_value_delimiter(writer)
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.inner.write_end_of_line(writer)
+    }
+
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.inner.write_null(writer)
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        self.inner.write_bool(writer, value)
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        self.inner.write_number(writer, value)
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        self.inner.write_string(writer, value)
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.inner.write_init_array(writer)
+    }
+
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.inner.write_init_object(writer)
+    }
+}
+
+/// The path of the current statement, for formatters that need its segments rather than a
+/// rendered key.
+///
+/// `push` renders each segment as a single byte, so the length of the key buffer is the depth
+/// of the current path.
+#[derive(Debug, Default)]
+pub(crate) struct KeyPath {
+    pub(crate) root: String,
+    keys: Vec<NamespaceKey>,
+}
+
+impl KeyPath {
+    /// Call from `write_key_segment`.
+    pub(crate) fn push(&mut self, key: &mut String, ns: &NamespaceKey) {
+        self.keys.truncate(key.len());
+        self.keys.push(ns.clone());
+        key.push('.');
+    }
+
+    /// Returns the root name and segments of the statement whose key buffer is `key`.
+    pub(crate) fn get(&mut self, key: &str) -> (&str, &[NamespaceKey]) {
+        self.keys.truncate(key.len());
+        (&self.root, &self.keys)
+    }
+}
+
 #[derive(Debug)]
 struct Context {
     ns_root: String,
     ns: Vec<NamespaceKey>,
+    /// The rendered key of the current position.
+    key: String,
+    /// The length of `key` before each entry of `ns` was appended.
+    key_lens: Vec<usize>,
+    /// Set while serializing a `serde_json::value::RawValue`, whose JSON text arrives as a
+    /// string.
+    raw_value: bool,
+    leaf_only: bool,
+    collapse_depth: Option<usize>,
+    collapse_leaves: Option<usize>,
+    /// In leaf-only mode, the container entered last, until something is written inside it.
+    pending_init: Option<Container>,
+    /// Set once `begin_document` has been called.
+    begun: bool,
+    /// Set when a whole value has been written, until `end_document` is called.
+    needs_end: bool,
 
     finish: bool,
 }
 
+#[derive(Debug, Clone, Copy)]
+enum Container {
+    Array,
+    Object,
+}
+
 impl Context {
     fn new() -> Context {
         Context::new_with_root_name("json")
@@ -119,6 +618,15 @@ impl Context {
         Context {
             ns_root: name.into(),
             ns: vec![],
+            key: String::new(),
+            key_lens: vec![],
+            raw_value: false,
+            leaf_only: false,
+            collapse_depth: None,
+            collapse_leaves: None,
+            pending_init: None,
+            begun: false,
+            needs_end: false,
             finish: false,
         }
     }
@@ -146,7 +654,7 @@ pub struct Serializer<W, F = RegularFormatter> {
 
 impl<W, F> Serializer<W, F>
 where
-    W: io::Write,
+    W: Write,
     F: Formatter<W> + Default,
 {
     pub fn new(writer: W) -> Self {
@@ -154,58 +662,208 @@ where
     }
 
     pub fn new_with_root_name(writer: W, root_name: impl Into<String>) -> Self {
-        Self {
-            writer,
-            formatter: F::default(),
-            ctx: Context::new_with_root_name(root_name),
-        }
+        Self::with_formatter(writer, F::default(), root_name)
     }
 }
 
 impl<W, F> Serializer<W, F>
 where
-    W: io::Write,
+    W: Write,
     F: Formatter<W>,
 {
+    pub fn with_formatter(writer: W, formatter: F, root_name: impl Into<String>) -> Self {
+        let mut ctx = Context::new_with_root_name(root_name);
+        let mut formatter = formatter;
+        formatter.write_root(&mut ctx.key, &ctx.ns_root);
+
+        Self {
+            writer,
+            formatter,
+            ctx,
+        }
+    }
+
+    pub fn with_options(writer: W, formatter: F, options: &Options) -> Self {
+        let mut ser = Self::with_formatter(writer, formatter, &*options.root_name);
+        ser.ctx.leaf_only = options.leaf_only;
+        ser.ctx.collapse_depth = options.collapse_depth;
+        ser.ctx.collapse_leaves = options.collapse_leaves;
+        ser
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub(crate) fn push_key(&mut self, key: NamespaceKey) {
+        self.ctx.key_lens.push(self.ctx.key.len());
+        self.formatter.write_key_segment(&mut self.ctx.key, &key);
+        self.ctx.ns.push(key);
+    }
+
+    pub(crate) fn pop_key(&mut self) -> Option<NamespaceKey> {
+        if let Some(len) = self.ctx.key_lens.pop() {
+            self.ctx.key.truncate(len);
+        }
+        self.ctx.ns.pop()
+    }
+
+    /// Writes a `serde_json::Value` directly, without going through `serde::Serializer`.
+    pub fn serialize_json_value(&mut self, value: &Value) -> Result<(), Error> {
+        use ser::Serializer as _;
+
+        match value {
+            Value::Null => self.serialize_unit(),
+            Value::Bool(b) => self.serialize_bool(*b),
+            Value::Number(n) => match (n.as_u64(), n.as_i64(), n.as_f64()) {
+                (Some(n), _, _) => self.serialize_number(n),
+                (_, Some(n), _) => self.serialize_number(n),
+                (_, _, Some(n)) => self.serialize_number(n),
+                _ => unreachable!(),
+            },
+            Value::String(s) => self.serialize_str(s),
+            Value::Array(_) | Value::Object(_) if self.should_collapse(value) => {
+                self.begin_statement()?;
+                self.formatter.write_json(&mut self.writer, value)?;
+                self.end_statement()?;
+                self.end_value();
+                Ok(())
+            }
+            Value::Array(array) => {
+                self.serialize_array_init()?;
+                for (i, v) in array.iter().enumerate() {
+                    self.push_key(NamespaceKey::Array(i));
+                    self.serialize_json_value(v)?;
+                    self.pop_key();
+                }
+                self.end_container()
+            }
+            Value::Object(object) => {
+                self.serialize_object_init()?;
+                for (k, v) in object.iter() {
+                    self.push_key(NamespaceKey::Object(k.clone()));
+                    self.serialize_json_value(v)?;
+                    self.pop_key();
+                }
+                self.end_container()
+            }
+        }
+    }
+
+    pub(crate) fn should_collapse(&self, value: &Value) -> bool {
+        let depth = self.ctx.ns.len();
+        self.ctx.collapse_depth.is_some_and(|d| depth >= d)
+            || self
+                .ctx
+                .collapse_leaves
+                .is_some_and(|n| count_leaves(value, n) < n)
+    }
+
     fn serialize_number<N: num::Num + Display>(&mut self, n: N) -> Result<(), Error> {
-        self.ctx.error_if_finished()?;
-        self.formatter
-            .write_key(&mut self.writer, &self.ctx.ns_root, &self.ctx.ns)?;
-        self.formatter.write_key_value_delimiter(&mut self.writer)?;
+        self.begin_statement()?;
         self.formatter.write_number(&mut self.writer, n)?;
-        self.formatter.write_end_of_line(&mut self.writer)?;
+        self.end_statement()?;
 
         if self.ctx.is_root() {
             self.ctx.finish = true;
         }
+        self.end_value();
 
         Ok(())
     }
 
-    fn serialize_array_init(&mut self) -> Result<(), Error> {
+    /// Writes the key and delimiter that start every statement.
+    fn begin_statement(&mut self) -> Result<(), Error> {
         self.ctx.error_if_finished()?;
-        self.formatter
-            .write_key(&mut self.writer, &self.ctx.ns_root, &self.ctx.ns)?;
-        self.formatter.write_key_value_delimiter(&mut self.writer)?;
-        self.formatter.write_init_array(&mut self.writer)?;
+        self.ctx.pending_init = None;
+        if !self.ctx.begun {
+            self.ctx.begun = true;
+            self.formatter.begin_document(&mut self.writer)?;
+        }
+        self.formatter.begin_statement(&mut self.writer)?;
+        self.formatter.write_key(&mut self.writer, &self.ctx.key)?;
+        self.formatter.write_key_value_delimiter(&mut self.writer)
+    }
+
+    fn end_statement(&mut self) -> Result<(), Error> {
         self.formatter.write_end_of_line(&mut self.writer)?;
+        self.formatter.end_statement(&mut self.writer)
+    }
+
+    /// Lets the formatter finish the output after the last statement.
+    ///
+    /// Must be called once the value has been serialized: formatters such as [`Aligned`] hold
+    /// statements back until then, and lose them otherwise. In debug builds with the `std`
+    /// feature, dropping a serializer that has written a whole value without calling this
+    /// panics.
+    pub fn end_document(&mut self) -> Result<(), Error> {
+        self.ctx.needs_end = false;
+        if !self.ctx.begun {
+            self.ctx.begun = true;
+            self.formatter.begin_document(&mut self.writer)?;
+        }
+        self.formatter.end_document(&mut self.writer)
+    }
 
-        Ok(())
+    pub(crate) fn serialize_array_init(&mut self) -> Result<(), Error> {
+        if self.ctx.leaf_only {
+            return self.defer_init(Container::Array);
+        }
+        self.write_init(Container::Array)
+    }
+
+    pub(crate) fn serialize_object_init(&mut self) -> Result<(), Error> {
+        if self.ctx.leaf_only {
+            return self.defer_init(Container::Object);
+        }
+        self.write_init(Container::Object)
+    }
+
+    fn write_init(&mut self, container: Container) -> Result<(), Error> {
+        self.begin_statement()?;
+        match container {
+            Container::Array => self.formatter.write_init_array(&mut self.writer)?,
+            Container::Object => self.formatter.write_init_object(&mut self.writer)?,
+        }
+        self.end_statement()
     }
 
-    fn serialize_object_init(&mut self) -> Result<(), Error> {
+    fn defer_init(&mut self, container: Container) -> Result<(), Error> {
         self.ctx.error_if_finished()?;
-        self.formatter
-            .write_key(&mut self.writer, &self.ctx.ns_root, &self.ctx.ns)?;
-        self.formatter.write_key_value_delimiter(&mut self.writer)?;
-        self.formatter.write_init_object(&mut self.writer)?;
-        self.formatter.write_end_of_line(&mut self.writer)?;
+        self.ctx.pending_init = Some(container);
+        Ok(())
+    }
 
+    /// Called when the current container ends. In leaf-only mode, writes its initialisation
+    /// if nothing was written inside it.
+    pub(crate) fn end_container(&mut self) -> Result<(), Error> {
+        if let Some(container) = self.ctx.pending_init.take() {
+            self.write_init(container)?;
+        }
+        self.end_value();
         Ok(())
     }
+
+    /// Called after a value has been written. At the root, the document is then due to end.
+    fn end_value(&mut self) {
+        if self.ctx.is_root() {
+            self.ctx.needs_end = true;
+        }
+    }
 }
 
-impl<'a, W: io::Write, F: Formatter<W>> ser::Serializer for &'a mut Serializer<W, F> {
+impl<W, F> Drop for Serializer<W, F> {
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        debug_assert!(
+            !self.ctx.needs_end || std::thread::panicking(),
+            "`Serializer::end_document` was not called after serializing a value"
+        );
+    }
+}
+
+impl<W: Write, F: Formatter<W>> ser::Serializer for &mut Serializer<W, F> {
     type Ok = ();
     type Error = Error;
 
@@ -218,16 +876,14 @@ impl<'a, W: io::Write, F: Formatter<W>> ser::Serializer for &'a mut Serializer<W
     type SerializeStructVariant = Self;
 
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
-        self.ctx.error_if_finished()?;
-
-        self.formatter
-            .write_key(&mut self.writer, &self.ctx.ns_root, &self.ctx.ns)?;
-        self.formatter.write_key_value_delimiter(&mut self.writer)?;
+        self.begin_statement()?;
         self.formatter.write_bool(&mut self.writer, v)?;
+        self.end_statement()?;
 
         if self.ctx.is_root() {
             self.ctx.finish = true;
         }
+        self.end_value();
 
         Ok(())
     }
@@ -277,16 +933,20 @@ impl<'a, W: io::Write, F: Formatter<W>> ser::Serializer for &'a mut Serializer<W
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-        self.ctx.error_if_finished()?;
-        self.formatter
-            .write_key(&mut self.writer, &self.ctx.ns_root, &self.ctx.ns)?;
-        self.formatter.write_key_value_delimiter(&mut self.writer)?;
+        if self.ctx.raw_value {
+            self.ctx.raw_value = false;
+            let mut de = serde_json::Deserializer::from_str(v);
+            return Transcoder::new(&mut de).serialize(self);
+        }
+
+        self.begin_statement()?;
         self.formatter.write_string(&mut self.writer, v)?;
-        self.formatter.write_end_of_line(&mut self.writer)?;
+        self.end_statement()?;
 
         if self.ctx.is_root() {
             self.ctx.finish = true;
         }
+        self.end_value();
 
         Ok(())
     }
@@ -306,23 +966,21 @@ impl<'a, W: io::Write, F: Formatter<W>> ser::Serializer for &'a mut Serializer<W
         self.serialize_unit()
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-        self.ctx.error_if_finished()?;
-        self.formatter
-            .write_key(&mut self.writer, &self.ctx.ns_root, &self.ctx.ns)?;
-        self.formatter.write_key_value_delimiter(&mut self.writer)?;
+        self.begin_statement()?;
         self.formatter.write_null(&mut self.writer)?;
-        self.formatter.write_end_of_line(&mut self.writer)?;
+        self.end_statement()?;
         if self.ctx.is_root() {
             self.ctx.finish = true;
         }
+        self.end_value();
         Ok(())
     }
 
@@ -339,33 +997,38 @@ impl<'a, W: io::Write, F: Formatter<W>> ser::Serializer for &'a mut Serializer<W
         self.serialize_str(variant)
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
         _name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _value: &T,
+        variant: &'static str,
+        value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        unimplemented!()
+        self.serialize_object_init()?;
+        self.push_key(NamespaceKey::Object(variant.to_string()));
+        value.serialize(&mut *self)?;
+        self.pop_key();
+        self.end_value();
+        Ok(())
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         self.serialize_array_init()?;
-        self.ctx.ns.push(NamespaceKey::Array(0));
+        self.push_key(NamespaceKey::Array(0));
         Ok(self)
     }
 
@@ -385,9 +1048,11 @@ impl<'a, W: io::Write, F: Formatter<W>> ser::Serializer for &'a mut Serializer<W
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.serialize_object_init()?;
+        self.push_key(NamespaceKey::Object(variant.to_string()));
         self.serialize_seq(Some(len))
     }
 
@@ -398,9 +1063,13 @@ impl<'a, W: io::Write, F: Formatter<W>> ser::Serializer for &'a mut Serializer<W
 
     fn serialize_struct(
         self,
-        _name: &'static str,
+        name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
+        if name == RAW_VALUE_TOKEN {
+            self.ctx.raw_value = true;
+            return Ok(self);
+        }
         self.serialize_map(Some(len))
     }
 
@@ -408,48 +1077,46 @@ impl<'a, W: io::Write, F: Formatter<W>> ser::Serializer for &'a mut Serializer<W
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.serialize_object_init()?;
+        self.push_key(NamespaceKey::Object(variant.to_string()));
         self.serialize_map(Some(len))
     }
 }
 
-impl<'a, W: io::Write, F: Formatter<W>> ser::SerializeSeq for &'a mut Serializer<W, F> {
+impl<W: Write, F: Formatter<W>> ser::SerializeSeq for &mut Serializer<W, F> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(&mut **self)?;
 
-        match self.ctx.ns.last_mut() {
-            Some(v) => match v {
-                NamespaceKey::Array(n) => *n += 1,
-                NamespaceKey::Object(_) => unreachable!(),
-            },
-
-            None => unreachable!(),
+        match self.pop_key() {
+            Some(NamespaceKey::Array(n)) => self.push_key(NamespaceKey::Array(n + 1)),
+            _ => unreachable!(),
         }
 
         Ok(())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.ctx.ns.pop();
-        Ok(())
+        self.pop_key();
+        self.end_container()
     }
 }
 
-impl<'a, W: io::Write, F: Formatter<W>> ser::SerializeTuple for &'a mut Serializer<W, F> {
+impl<W: Write, F: Formatter<W>> ser::SerializeTuple for &mut Serializer<W, F> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         ser::SerializeSeq::serialize_element(self, value)
     }
@@ -459,49 +1126,45 @@ impl<'a, W: io::Write, F: Formatter<W>> ser::SerializeTuple for &'a mut Serializ
     }
 }
 
-impl<'a, W: io::Write, F: Formatter<W>> ser::SerializeMap for &'a mut Serializer<W, F> {
+impl<W: Write, F: Formatter<W>> ser::SerializeMap for &mut Serializer<W, F> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<(), Self::Error>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        let key = serde_json::to_string(key)
-            .map_err(Error::Serialize)?
-            .trim_matches('"')
-            .to_string();
-        self.ctx.ns.push(NamespaceKey::Object(key));
+        let key = key.serialize(MapKeySerializer)?;
+        self.push_key(NamespaceKey::Object(key));
 
         Ok(())
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(&mut **self)?;
-        self.ctx.ns.pop();
+        self.pop_key();
         Ok(())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        self.end_container()
     }
 }
 
-impl<'a, W: io::Write, F: Formatter<W>> ser::SerializeStruct for &'a mut Serializer<W, F> {
+impl<W: Write, F: Formatter<W>> ser::SerializeStruct for &mut Serializer<W, F> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
+        if self.ctx.raw_value {
+            return value.serialize(&mut **self);
+        }
         ser::SerializeMap::serialize_entry(self, key, value)
     }
 
@@ -510,13 +1173,13 @@ impl<'a, W: io::Write, F: Formatter<W>> ser::SerializeStruct for &'a mut Seriali
     }
 }
 
-impl<'a, W: io::Write, F: Formatter<W>> ser::SerializeTupleStruct for &'a mut Serializer<W, F> {
+impl<W: Write, F: Formatter<W>> ser::SerializeTupleStruct for &mut Serializer<W, F> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         ser::SerializeSeq::serialize_element(self, value)
     }
@@ -526,170 +1189,418 @@ impl<'a, W: io::Write, F: Formatter<W>> ser::SerializeTupleStruct for &'a mut Se
     }
 }
 
-impl<'a, W: io::Write, F: Formatter<W>> ser::SerializeTupleVariant for &'a mut Serializer<W, F> {
+impl<W: Write, F: Formatter<W>> ser::SerializeTupleVariant for &mut Serializer<W, F> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         ser::SerializeSeq::serialize_element(self, value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        ser::SerializeSeq::end(self)
+        ser::SerializeSeq::end(&mut *self)?;
+        self.pop_key();
+        self.end_value();
+        Ok(())
     }
 }
 
-impl<'a, W: io::Write, F: Formatter<W>> ser::SerializeStructVariant for &'a mut Serializer<W, F> {
+impl<W: Write, F: Formatter<W>> ser::SerializeStructVariant for &mut Serializer<W, F> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         ser::SerializeMap::serialize_entry(self, key, value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        ser::SerializeMap::end(self)
+        ser::SerializeMap::end(&mut *self)?;
+        self.pop_key();
+        self.end_value();
+        Ok(())
     }
 }
 
-static RE_OBJECT_KEY: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9_]*$").unwrap());
-#[derive(Debug, Default)]
-pub struct RegularFormatter;
-
-impl<W: io::Write> Formatter<W> for RegularFormatter {
-    fn write_key(&self, writer: &mut W, ns_root: &str, nss: &[NamespaceKey]) -> Result<(), Error> {
-        use std::fmt::Write as _;
-        let mut res = String::new();
-        res.push_str(ns_root);
-        for ns in nss.iter() {
-            match ns {
-                NamespaceKey::Array(n) => write!(res, "[{n}]").unwrap(),
-                NamespaceKey::Object(k) => {
-                    if RE_OBJECT_KEY.is_match(k) {
-                        write!(res, ".{k}").unwrap();
-                    } else {
-                        write!(res, "[\"{k}\"]").unwrap();
+/// Counts the scalars and empty containers in `value`, stopping once `limit` is reached.
+fn count_leaves(value: &Value, limit: usize) -> usize {
+    fn count(value: &Value, limit: usize, n: &mut usize) {
+        match value {
+            Value::Array(array) if !array.is_empty() => {
+                for child in array {
+                    if *n >= limit {
+                        return;
+                    }
+                    count(child, limit, n);
+                }
+            }
+            Value::Object(object) if !object.is_empty() => {
+                for child in object.values() {
+                    if *n >= limit {
+                        return;
                     }
+                    count(child, limit, n);
                 }
-            };
+            }
+            _ => *n += 1,
         }
+    }
+
+    let mut n = 0;
+    count(value, limit, &mut n);
+    n
+}
+
+/// Serializes map keys to the plain strings used in [`NamespaceKey::Object`].
+struct MapKeySerializer;
 
-        write!(writer, "{res}").map_err(Error::Io)
+fn key_must_be_a_string() -> Error {
+    Error::KeyMustBeAString
+}
+
+impl ser::Serializer for MapKeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<String, Error>;
+    type SerializeTuple = ser::Impossible<String, Error>;
+    type SerializeTupleStruct = ser::Impossible<String, Error>;
+    type SerializeTupleVariant = ser::Impossible<String, Error>;
+    type SerializeMap = ser::Impossible<String, Error>;
+    type SerializeStruct = ser::Impossible<String, Error>;
+    type SerializeStructVariant = ser::Impossible<String, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
     }
 
-    fn write_key_value_delimiter(&self, wriiter: &mut W) -> Result<(), Error> {
-        write!(wriiter, " = ").map_err(Error::Io)
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
     }
 
-    fn write_end_of_line(&self, writer: &mut W) -> Result<(), Error> {
-        writeln!(writer, ";").map_err(Error::Io)
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
     }
 
-    fn write_null(&self, writer: &mut W) -> Result<(), Error> {
-        write!(writer, "null").map_err(Error::Io)
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
     }
 
-    fn write_bool(&self, writer: &mut W, value: bool) -> Result<(), Error> {
-        write!(writer, "{value}").map_err(Error::Io)
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Err(key_must_be_a_string())
     }
 
-    fn write_number<N: num::Num + Display>(&self, writer: &mut W, value: N) -> Result<(), Error> {
-        write!(writer, "{value}").map_err(Error::Io)
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(key_must_be_a_string())
     }
 
-    fn write_string(&self, writer: &mut W, value: &str) -> Result<(), Error> {
-        write!(writer, "\"{value}\"").map_err(Error::Io)
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
     }
 
-    fn write_init_array(&self, writer: &mut W) -> Result<(), Error> {
-        write!(writer, "[]").map_err(Error::Io)
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(key_must_be_a_string())
     }
 
-    fn write_init_object(&self, writer: &mut W) -> Result<(), Error> {
-        write!(writer, "{{}}").map_err(Error::Io)
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Err(key_must_be_a_string())
     }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(key_must_be_a_string())
+    }
+}
+
+/// Returns whether an object key can be written with dot notation (`json.key`) instead of
+/// bracket notation (`json["key"]`).
+pub(crate) fn is_identifier(key: &str) -> bool {
+    let mut bytes = key.bytes();
+    matches!(bytes.next(), Some(b) if b.is_ascii_alphabetic())
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// Returns whether `N` is a floating point type, i.e. whether `1 / 2` is not truncated to `0`.
+pub(crate) fn is_float<N: num::Num>() -> bool {
+    N::one() / (N::one() + N::one()) != N::zero()
 }
 
 #[derive(Debug, Default)]
-pub struct ColorFormatter;
-
-impl<W: io::Write> Formatter<W> for ColorFormatter {
-    fn write_key(&self, writer: &mut W, ns_root: &str, nss: &[NamespaceKey]) -> Result<(), Error> {
-        use colored::Colorize;
-        use std::fmt::Write as _;
-
-        let mut res = String::new();
-        write!(res, "{}", ns_root.blue()).unwrap();
-        for ns in nss.iter() {
-            match ns {
-                NamespaceKey::Array(n) => {
-                    let sb = "[".magenta();
-                    let eb = "]".magenta();
-                    let n = n.to_string().red();
-                    write!(res, "{sb}{n}{eb}").unwrap();
-                }
-                NamespaceKey::Object(k) => {
-                    if RE_OBJECT_KEY.is_match(k) {
-                        let k = k.blue();
-                        write!(res, ".{k}").unwrap();
-                    } else {
-                        let k = k.yellow();
-                        write!(res, "[\"{k}\"]").unwrap();
-                    }
+pub struct RegularFormatter {
+    syntax: LineSyntax,
+}
+
+impl RegularFormatter {
+    pub fn with_syntax(syntax: LineSyntax) -> Self {
+        RegularFormatter { syntax }
+    }
+}
+
+impl<W: Write> Formatter<W> for RegularFormatter {
+    fn write_root(&mut self, key: &mut String, ns_root: &str) {
+        key.push_str(ns_root);
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        match ns {
+            NamespaceKey::Array(n) => write!(key, "[{n}]").unwrap(),
+            NamespaceKey::Object(k) => {
+                if is_identifier(k) {
+                    write!(key, ".{k}").unwrap();
+                } else {
+                    key.push('[');
+                    write_json_string(k, |s| key.write_str(s)).unwrap();
+                    key.push(']');
                 }
-            };
-        }
+            }
+        };
+    }
+
+    fn write_key_value_delimiter(&mut self, wriiter: &mut W) -> Result<(), Error> {
+        wriiter.write_str(self.syntax.delimiter())
+    }
+
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str(self.syntax.end_of_line())
+    }
+
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+        write!(writer, "null")
+    }
+
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        write!(writer, "{value}")
+    }
+
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        write_json_string(value, |s| writer.write_str(s))
+    }
+
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        write!(writer, "[]")
+    }
 
-        write!(writer, "{res}").map_err(Error::Io)
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        write!(writer, "{{}}")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ColorFormatter {
+    syntax: LineSyntax,
+    theme: ColorTheme,
+}
+
+impl ColorFormatter {
+    pub fn new(syntax: LineSyntax, theme: ColorTheme) -> Self {
+        ColorFormatter { syntax, theme }
     }
 
-    fn write_key_value_delimiter(&self, wriiter: &mut W) -> Result<(), Error> {
-        write!(wriiter, " = ").map_err(Error::Io)
+    fn write_painted<W: Write>(
+        &self,
+        writer: &mut W,
+        style: Style,
+        text: &str,
+    ) -> Result<(), Error> {
+        let mut s = String::new();
+        style.paint(&mut s, text, self.theme.depth);
+        writer.write_str(&s)
+    }
+}
+
+impl<W: Write> Formatter<W> for ColorFormatter {
+    fn write_root(&mut self, key: &mut String, ns_root: &str) {
+        self.theme.key.paint(key, ns_root, self.theme.depth);
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        let ColorTheme { depth, .. } = self.theme;
+        match ns {
+            NamespaceKey::Array(n) => {
+                self.theme.bracket.paint(key, "[", depth);
+                self.theme.number.paint(key, &n.to_string(), depth);
+                self.theme.bracket.paint(key, "]", depth);
+            }
+            NamespaceKey::Object(k) => {
+                if is_identifier(k) {
+                    key.push('.');
+                    self.theme.key.paint(key, k, depth);
+                } else {
+                    let mut quoted = String::new();
+                    write_json_string(k, |s| quoted.write_str(s)).unwrap();
+                    key.push_str("[\"");
+                    self.theme
+                        .string
+                        .paint(key, &quoted[1..quoted.len() - 1], depth);
+                    key.push_str("\"]");
+                }
+            }
+        };
     }
 
-    fn write_end_of_line(&self, writer: &mut W) -> Result<(), Error> {
-        writeln!(writer, ";").map_err(Error::Io)
+    fn write_key_value_delimiter(&mut self, wriiter: &mut W) -> Result<(), Error> {
+        wriiter.write_str(self.syntax.delimiter())
     }
 
-    fn write_null(&self, writer: &mut W) -> Result<(), Error> {
-        use colored::Colorize;
-        write!(writer, "{}", "null".cyan()).map_err(Error::Io)
+    fn write_end_of_line(&mut self, writer: &mut W) -> Result<(), Error> {
+        writer.write_str(self.syntax.end_of_line())
     }
 
-    fn write_bool(&self, writer: &mut W, value: bool) -> Result<(), Error> {
-        use colored::Colorize;
-        write!(writer, "{}", value.to_string().cyan()).map_err(Error::Io)
+    fn write_null(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.write_painted(writer, self.theme.null, "null")
     }
 
-    fn write_number<N: num::Num + Display>(&self, writer: &mut W, value: N) -> Result<(), Error> {
-        use colored::Colorize;
-        write!(writer, "{}", value.to_string().red()).map_err(Error::Io)
+    fn write_bool(&mut self, writer: &mut W, value: bool) -> Result<(), Error> {
+        self.write_painted(writer, self.theme.bool, &value.to_string())
     }
 
-    fn write_string(&self, writer: &mut W, value: &str) -> Result<(), Error> {
-        use colored::Colorize;
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        self.write_painted(writer, self.theme.number, &value.to_string())
+    }
 
-        let s = format!("\"{}\"", value).yellow();
-        write!(writer, "{s}").map_err(Error::Io)
+    fn write_string(&mut self, writer: &mut W, value: &str) -> Result<(), Error> {
+        let mut quoted = String::new();
+        write_json_string(value, |s| quoted.write_str(s)).unwrap();
+        self.write_painted(writer, self.theme.string, &quoted)
     }
 
-    fn write_init_array(&self, writer: &mut W) -> Result<(), Error> {
-        use colored::Colorize;
-        write!(writer, "{}", "[]".magenta()).map_err(Error::Io)
+    fn write_init_array(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.write_painted(writer, self.theme.bracket, "[]")
     }
 
-    fn write_init_object(&self, writer: &mut W) -> Result<(), Error> {
-        use colored::Colorize;
-        write!(writer, "{}", "{}".magenta()).map_err(Error::Io)
+    fn write_init_object(&mut self, writer: &mut W) -> Result<(), Error> {
+        self.write_painted(writer, self.theme.bracket, "{}")
     }
 }
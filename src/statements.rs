@@ -0,0 +1,339 @@
+//! Flattens a value into gron statements without rendering any text.
+
+use serde::Serialize;
+use serde_json::{map, Map, Number, Value};
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::{self, Vec},
+};
+use core::{fmt::Display, iter::Enumerate};
+
+use crate::{
+    ser::{is_float, Formatter, KeyPath, NamespaceKey, Serializer},
+    Error, Path, Statement, Write,
+};
+
+/// Returns the statements `to_string` would write for `value`, as structured values.
+///
+/// ```
+/// use serde_json::json;
+///
+/// let statements: Vec<_> = serde_gron::to_statements(&json!({ "a": [1] }))
+///     .unwrap()
+///     .map(|s| s.to_string())
+///     .collect();
+/// assert_eq!(statements, ["json = {};", "json.a = [];", "json.a[0] = 1;"]);
+/// ```
+pub fn to_statements(value: &impl Serialize) -> Result<Statements, Error> {
+    to_statements_with(value, "json")
+}
+
+/// Like [`to_statements`], with a custom root name.
+///
+/// `value` goes through the same serializer as [`to_string`](crate::to_string), so the
+/// statements come in the same order and the same values are rejected. They are all
+/// collected before the iterator is returned. NaN and infinite floats, which `to_string`
+/// writes as `NaN` and `inf`, are an error, since a `serde_json::Value` cannot hold them.
+///
+/// Use [`value_to_statements`] to flatten a `serde_json::Value` you already own one
+/// statement at a time.
+pub fn to_statements_with(
+    value: &impl Serialize,
+    root_name: impl Into<String>,
+) -> Result<Statements, Error> {
+    let mut statements = Vec::new();
+    let collector = Collector {
+        statements: &mut statements,
+        key_path: KeyPath::default(),
+        path: None,
+        value: None,
+    };
+    let mut ser = Serializer::with_formatter(Discard, collector, root_name);
+    value.serialize(&mut ser)?;
+    ser.end_document()?;
+    drop(ser);
+
+    Ok(Statements(Source::Collected(statements.into_iter())))
+}
+
+/// Like [`to_statements`], but takes ownership of a `serde_json::Value` so leaves are moved
+/// rather than copied.
+pub fn value_to_statements(value: Value) -> Statements {
+    Statements::new(Path::new("json"), value)
+}
+
+/// A writer for formatters that keep what they are given instead of writing it.
+struct Discard;
+
+impl Write for Discard {
+    fn write_str(&mut self, _s: &str) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Collects each statement the serializer writes as a path and a value.
+struct Collector<'a> {
+    statements: &'a mut Vec<Statement>,
+    key_path: KeyPath,
+    /// The path of the statement being written.
+    path: Option<Path>,
+    /// The value of the statement being written.
+    value: Option<Value>,
+}
+
+impl<W: Write> Formatter<W> for Collector<'_> {
+    fn write_root(&mut self, _key: &mut String, ns_root: &str) {
+        self.key_path.root = ns_root.to_string();
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        self.key_path.push(key, ns);
+    }
+
+    fn write_key(&mut self, _writer: &mut W, key: &str) -> Result<(), Error> {
+        let (root, keys) = self.key_path.get(key);
+        self.path = Some(Path::with_keys(root, keys.to_vec()));
+        Ok(())
+    }
+
+    fn write_key_value_delimiter(&mut self, _writer: &mut W) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_end_of_line(&mut self, _writer: &mut W) -> Result<(), Error> {
+        if let (Some(path), Some(value)) = (self.path.take(), self.value.take()) {
+            self.statements.push(Statement::Assign(path, value));
+        }
+        Ok(())
+    }
+
+    fn write_null(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.value = Some(Value::Null);
+        Ok(())
+    }
+
+    fn write_bool(&mut self, _writer: &mut W, value: bool) -> Result<(), Error> {
+        self.value = Some(Value::Bool(value));
+        Ok(())
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        _writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        let text = value.to_string();
+        let number = if is_float::<N>() {
+            text.parse().ok().and_then(Number::from_f64)
+        } else {
+            text.parse().ok()
+        };
+        let number =
+            number.ok_or_else(|| Error::Custom(format!("`{text}` is not a valid JSON number")))?;
+        self.value = Some(Value::Number(number));
+        Ok(())
+    }
+
+    fn write_string(&mut self, _writer: &mut W, value: &str) -> Result<(), Error> {
+        self.value = Some(Value::String(value.to_string()));
+        Ok(())
+    }
+
+    fn write_init_array(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.value = Some(Value::Array(Vec::new()));
+        Ok(())
+    }
+
+    fn write_init_object(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.value = Some(Value::Object(Map::new()));
+        Ok(())
+    }
+
+    fn write_json(&mut self, _writer: &mut W, value: &Value) -> Result<(), Error> {
+        self.value = Some(value.clone());
+        Ok(())
+    }
+}
+
+/// An iterator over the [`Statement::Assign`]s of a value, in the order `to_string` writes
+/// them: each container's initializer comes before its members.
+#[derive(Debug)]
+pub struct Statements(Source);
+
+#[derive(Debug)]
+enum Source {
+    /// Statements already collected from the serializer.
+    Collected(vec::IntoIter<Statement>),
+    /// A value flattened as the iterator advances.
+    Value(Flatten),
+}
+
+#[derive(Debug)]
+struct Flatten {
+    path: Path,
+    stack: Vec<Frame>,
+    next: Option<Value>,
+}
+
+#[derive(Debug)]
+enum Frame {
+    Array(Enumerate<vec::IntoIter<Value>>),
+    Object(map::IntoIter),
+}
+
+impl Iterator for Frame {
+    type Item = (NamespaceKey, Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Frame::Array(iter) => iter.next().map(|(i, v)| (NamespaceKey::Array(i), v)),
+            Frame::Object(iter) => iter.next().map(|(k, v)| (NamespaceKey::Object(k), v)),
+        }
+    }
+}
+
+impl Statements {
+    /// Flattens `value`, prefixing every statement with `path`.
+    pub(crate) fn new(path: Path, value: Value) -> Statements {
+        Statements(Source::Value(Flatten {
+            path,
+            stack: Vec::new(),
+            next: Some(value),
+        }))
+    }
+}
+
+impl Iterator for Statements {
+    type Item = Statement;
+
+    fn next(&mut self) -> Option<Statement> {
+        match &mut self.0 {
+            Source::Collected(iter) => iter.next(),
+            Source::Value(flatten) => flatten.next(),
+        }
+    }
+}
+
+impl Iterator for Flatten {
+    type Item = Statement;
+
+    fn next(&mut self) -> Option<Statement> {
+        loop {
+            if let Some(value) = self.next.take() {
+                let init = match value {
+                    Value::Array(array) => {
+                        self.stack.push(Frame::Array(array.into_iter().enumerate()));
+                        Value::Array(Vec::new())
+                    }
+                    Value::Object(object) => {
+                        self.stack.push(Frame::Object(object.into_iter()));
+                        Value::Object(Map::new())
+                    }
+                    scalar => {
+                        let statement = Statement::Assign(self.path.clone(), scalar);
+                        if !self.stack.is_empty() {
+                            self.path.pop();
+                        }
+                        return Some(statement);
+                    }
+                };
+                return Some(Statement::Assign(self.path.clone(), init));
+            }
+
+            let frame = self.stack.last_mut()?;
+            match frame.next() {
+                Some((key, child)) => {
+                    self.path.push(key);
+                    self.next = Some(child);
+                }
+                None => {
+                    self.stack.pop();
+                    if !self.stack.is_empty() {
+                        self.path.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_to_statements_matches_to_string() {
+        let value = json!({
+            "a": [1, [], {}, [null, { "b-c": "d" }]],
+            "e": { "f": true },
+            "g": -2.5
+        });
+        let text: String = to_statements(&value)
+            .unwrap()
+            .map(|s| format!("{s}\n"))
+            .collect();
+        assert_eq!(text, crate::to_string(&value).unwrap());
+
+        let statements: Vec<_> = to_statements_with(&"x", "root").unwrap().collect();
+        assert_eq!(
+            statements,
+            [Statement::Assign(Path::new("root"), json!("x"))]
+        );
+    }
+
+    #[test]
+    fn test_to_statements_follows_serializer() {
+        #[derive(serde::Serialize)]
+        struct S {
+            z: u8,
+            a: Vec<f64>,
+            m: Option<()>,
+        }
+
+        let s = S {
+            z: 1,
+            a: vec![0.5, -2.25],
+            m: None,
+        };
+        let statements: Vec<_> = to_statements(&s).unwrap().collect();
+        let parsed = crate::parse_str(&crate::to_string(&s).unwrap()).unwrap();
+        assert_eq!(statements, parsed);
+        assert_eq!(
+            statements[1],
+            Statement::Assign(
+                Path::with_keys("json", vec![NamespaceKey::Object("z".into())]),
+                json!(1)
+            )
+        );
+
+        assert!(to_statements(&1u128).is_err());
+        assert!(to_statements(&[f64::NAN]).is_err());
+    }
+
+    #[test]
+    fn test_value_to_statements() {
+        let statements: Vec<_> = value_to_statements(json!([{ "a": 1 }])).collect();
+        assert_eq!(
+            statements,
+            [
+                Statement::Assign(Path::new("json"), json!([])),
+                Statement::Assign(
+                    Path::with_keys("json", vec![NamespaceKey::Array(0)]),
+                    json!({})
+                ),
+                Statement::Assign(
+                    Path::with_keys(
+                        "json",
+                        vec![NamespaceKey::Array(0), NamespaceKey::Object("a".into())]
+                    ),
+                    json!(1)
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use serde::{
+    de::{
+        self,
+        value::{MapAccessDeserializer, MapDeserializer, SeqDeserializer},
+        IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any,
+};
+use serde_json::Value;
+
+use alloc::{format, string::ToString, vec::Vec};
+
+use crate::{apply::MAX_PADDING, Error};
+
+/// Deserializes a tree whose leaves are strings, parsing each leaf into the type that is
+/// asked for. Used for formats without typed values, such as environment variables.
+pub(crate) struct StringValue<'a>(pub(crate) &'a Value);
+
+static NULL: Value = Value::Null;
+
+/// Parses an object key written for an array index, rejecting forms such as `01` that would
+/// not be written for one.
+fn index(key: &str) -> Option<usize> {
+    if !key.bytes().all(|b| b.is_ascii_digit()) || (key.len() > 1 && key.starts_with('0')) {
+        return None;
+    }
+    key.parse().ok()
+}
+
+macro_rules! parse {
+    ($($deserialize:ident => $visit:ident,)*) => {
+        $(
+            fn $deserialize<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                match self.0 {
+                    Value::String(s) => visitor.$visit(s.parse().map_err(|e| {
+                        de::Error::custom(format_args!("invalid value `{s}`: {e}"))
+                    })?),
+                    _ => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for StringValue<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::Array(array) => {
+                let mut seq = SeqDeserializer::new(array.iter().map(StringValue));
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Object(object) => {
+                let entries = object.iter().map(|(k, v)| (k.as_str(), StringValue(v)));
+                let mut map = MapDeserializer::new(entries);
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            Value::String(s) => visitor.visit_str(s),
+            _ => visitor.visit_unit(),
+        }
+    }
+
+    parse! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            Value::String(s) if s.is_empty() => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::String(s) if s.is_empty() => visitor.visit_unit(),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::String(s) if s == "[]" => {
+                StringValue(&Value::Array(Vec::new())).deserialize_any(visitor)
+            }
+            // Names without array syntax, such as environment variables, give objects keyed by
+            // index.
+            Value::Object(object) => {
+                let mut entries: Vec<_> = object
+                    .iter()
+                    .filter_map(|(k, v)| Some((index(k)?, v)))
+                    .collect();
+                if entries.len() < object.len() {
+                    return self.deserialize_any(visitor);
+                }
+                entries.sort_unstable_by_key(|&(i, _)| i);
+                let len = entries.last().map_or(0, |&(i, _)| i + 1);
+                if len - entries.len() > MAX_PADDING {
+                    return Err(Error::Custom(format!(
+                        "Array index {} leaves more than {MAX_PADDING} elements unset",
+                        len - 1
+                    )));
+                }
+
+                let mut entries = entries.into_iter().peekable();
+                let elements = (0..len).map(|i| match entries.next_if(|&(j, _)| j == i) {
+                    Some((_, v)) => StringValue(v),
+                    None => StringValue(&NULL),
+                });
+                let mut seq = SeqDeserializer::new(elements);
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::String(s) if s == "{}" => {
+                StringValue(&Value::Object(Default::default())).deserialize_any(visitor)
+            }
+            // Numeric segments were taken for array indexes, but may have been map keys.
+            Value::Array(array) => {
+                let entries = array
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, v)| !v.is_null())
+                    .map(|(i, v)| (i.to_string(), StringValue(v)));
+                let mut map = MapDeserializer::new(entries);
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::String(s) => visitor.visit_enum(s.as_str().into_deserializer()),
+            Value::Object(object) => {
+                let entries = object.iter().map(|(k, v)| (k.as_str(), StringValue(v)));
+                visitor.visit_enum(MapAccessDeserializer::new(MapDeserializer::new(entries)))
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit_struct identifier ignored_any
+    }
+}
+
+impl<'de, 'a> IntoDeserializer<'de, Error> for StringValue<'a> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
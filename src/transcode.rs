@@ -0,0 +1,348 @@
+//! Streams values from any `serde::Deserializer` into the gron serializer.
+//!
+//! Every value is forwarded to the serializer as soon as the deserializer produces it, so
+//! memory use is proportional to the nesting depth of the document rather than its size.
+//! This does not hold when [`Options::collapse_depth`] or [`Options::collapse_leaves`] is
+//! set: collapsing depends on the size of each subtree, so the whole document is read into a
+//! `serde_json::Value` first.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{cell::RefCell, fmt};
+use serde::{
+    de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor},
+    ser::{self, Serialize, SerializeMap, SerializeSeq, Serializer},
+};
+
+use crate::{to_writer_with_options, Error, FormatType, Options, Write};
+
+/// Writes the document read from `deserializer` to `writer` as gron.
+///
+/// ```
+/// let mut de = serde_json::Deserializer::from_str(r#"{"a": [1, 2]}"#);
+/// let mut writer = vec![];
+/// serde_gron::transcode(&mut de, &mut writer).unwrap();
+/// assert_eq!(
+///     String::from_utf8(writer).unwrap(),
+///     "json = {};\njson.a = [];\njson.a[0] = 1;\njson.a[1] = 2;\n"
+/// );
+/// ```
+pub fn transcode<'de, D>(deserializer: D, writer: impl Write) -> Result<(), Error>
+where
+    D: Deserializer<'de>,
+{
+    transcode_with(deserializer, writer, "json", FormatType::Regular)
+}
+
+pub fn transcode_with<'de, D>(
+    deserializer: D,
+    writer: impl Write,
+    root_name: impl Into<String>,
+    format_type: FormatType,
+) -> Result<(), Error>
+where
+    D: Deserializer<'de>,
+{
+    transcode_with_options(deserializer, writer, &Options::new(root_name, format_type))
+}
+
+/// Like [`transcode`], with the output configured by `options`.
+///
+/// If `options` collapses containers, the whole document is read into a `serde_json::Value`
+/// before anything is written, as with [`to_writer_with_options`].
+pub fn transcode_with_options<'de, D>(
+    deserializer: D,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error>
+where
+    D: Deserializer<'de>,
+{
+    // Errors pass through the deserializer as messages, so keep the writer's own error to
+    // return in their place, e.g. for callers that check for `io::ErrorKind::BrokenPipe`.
+    let mut writer = KeepError {
+        inner: writer,
+        error: None,
+    };
+    to_writer_with_options(&Transcoder::new(deserializer), &mut writer, options)
+        .map_err(|e| writer.error.take().unwrap_or(e))
+}
+
+/// Remembers the first error of the writer it wraps.
+struct KeepError<W> {
+    inner: W,
+    error: Option<Error>,
+}
+
+impl<W: Write> Write for &mut KeepError<W> {
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.inner.write_str(s).map_err(|e| {
+            let message = e.to_string();
+            self.error.get_or_insert(e);
+            Error::Custom(message)
+        })
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.inner.is_terminal()
+    }
+}
+
+/// A `Serialize` adapter that pulls its value from a deserializer when serialized.
+///
+/// It may only be serialized once.
+pub(crate) struct Transcoder<D>(RefCell<Option<D>>);
+
+impl<D> Transcoder<D> {
+    pub(crate) fn new(deserializer: D) -> Transcoder<D> {
+        Transcoder(RefCell::new(Some(deserializer)))
+    }
+}
+
+impl<'de, D> Serialize for Transcoder<D>
+where
+    D: Deserializer<'de>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let deserializer = self
+            .0
+            .borrow_mut()
+            .take()
+            .expect("Transcoder can only be serialized once");
+        deserializer
+            .deserialize_any(ForwardVisitor(serializer))
+            .map_err(ser::Error::custom)
+    }
+}
+
+struct ForwardVisitor<S>(S);
+
+macro_rules! forward {
+    ($($visit:ident => $serialize:ident($ty:ty),)*) => {
+        $(
+            fn $visit<E>(self, v: $ty) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.0.$serialize(v).map_err(de::Error::custom)
+            }
+        )*
+    };
+}
+
+impl<'de, S> Visitor<'de> for ForwardVisitor<S>
+where
+    S: Serializer,
+{
+    type Value = S::Ok;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    forward! {
+        visit_bool => serialize_bool(bool),
+        visit_i8 => serialize_i8(i8),
+        visit_i16 => serialize_i16(i16),
+        visit_i32 => serialize_i32(i32),
+        visit_i64 => serialize_i64(i64),
+        visit_i128 => serialize_i128(i128),
+        visit_u8 => serialize_u8(u8),
+        visit_u16 => serialize_u16(u16),
+        visit_u32 => serialize_u32(u32),
+        visit_u64 => serialize_u64(u64),
+        visit_u128 => serialize_u128(u128),
+        visit_f32 => serialize_f32(f32),
+        visit_f64 => serialize_f64(f64),
+        visit_char => serialize_char(char),
+        visit_str => serialize_str(&str),
+        visit_bytes => serialize_bytes(&[u8]),
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(&v)
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_bytes(&v)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.serialize_unit().map_err(de::Error::custom)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.serialize_none().map_err(de::Error::custom)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0
+            .serialize_some(&Transcoder::new(deserializer))
+            .map_err(de::Error::custom)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0
+            .serialize_newtype_struct("<newtype>", &Transcoder::new(deserializer))
+            .map_err(de::Error::custom)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut ser = self
+            .0
+            .serialize_seq(seq.size_hint())
+            .map_err(de::Error::custom)?;
+        while seq.next_element_seed(ElementSeed(&mut ser))?.is_some() {}
+        ser.end().map_err(de::Error::custom)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut ser = self
+            .0
+            .serialize_map(map.size_hint())
+            .map_err(de::Error::custom)?;
+        while map.next_key_seed(KeySeed(&mut ser))?.is_some() {
+            map.next_value_seed(ValueSeed(&mut ser))?;
+        }
+        ser.end().map_err(de::Error::custom)
+    }
+}
+
+struct ElementSeed<'a, S>(&'a mut S);
+
+impl<'de, S> DeserializeSeed<'de> for ElementSeed<'_, S>
+where
+    S: SerializeSeq,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0
+            .serialize_element(&Transcoder::new(deserializer))
+            .map_err(de::Error::custom)
+    }
+}
+
+struct KeySeed<'a, S>(&'a mut S);
+
+impl<'de, S> DeserializeSeed<'de> for KeySeed<'_, S>
+where
+    S: SerializeMap,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0
+            .serialize_key(&Transcoder::new(deserializer))
+            .map_err(de::Error::custom)
+    }
+}
+
+struct ValueSeed<'a, S>(&'a mut S);
+
+impl<'de, S> DeserializeSeed<'de> for ValueSeed<'_, S>
+where
+    S: SerializeMap,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0
+            .serialize_value(&Transcoder::new(deserializer))
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::FmtWriter;
+
+    #[test]
+    fn test_transcode_matches_to_string() {
+        let value = json!({ "a": [1, -2.5, null, true], "b": { "c-d": "e" } });
+        let input = value.to_string();
+
+        let mut output = String::new();
+        transcode(
+            &mut serde_json::Deserializer::from_str(&input),
+            FmtWriter(&mut output),
+        )
+        .unwrap();
+        assert_eq!(output, crate::to_string(&value).unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_transcode_keeps_writer_error() {
+        struct BrokenPipe;
+
+        impl std::io::Write for BrokenPipe {
+            fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let result = transcode(
+            &mut serde_json::Deserializer::from_str("{\"a\": [1]}"),
+            BrokenPipe,
+        );
+        assert!(matches!(
+            result,
+            Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::BrokenPipe
+        ));
+    }
+
+    #[test]
+    fn test_transcode_error() {
+        assert!(transcode(
+            &mut serde_json::Deserializer::from_str("{\"a\": [1,"),
+            FmtWriter(String::new())
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! An indented tree of the statements, e.g. `├── users` above `│   └── [0]`.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{Display, Write as _},
+    mem,
+};
+
+use crate::{
+    color::Style,
+    languages::serialize,
+    ser::{is_identifier, Formatter, NamespaceKey},
+    write::{write_json_string, FmtWriter},
+    ColorTheme, Error, Options, Write,
+};
+
+/// Writes each statement as a node of a tree drawn with box-drawing characters.
+///
+/// Whether a node is the last child of its parent is only known once the statements after it
+/// have been written, so the whole document is buffered and written by `end_document`.
+#[derive(Debug, Default)]
+struct TreeFormatter {
+    /// The colors of the tokens, or `None` for plain output.
+    theme: Option<ColorTheme>,
+    line: Node,
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    depth: usize,
+    label: String,
+    value: String,
+    container: bool,
+}
+
+impl TreeFormatter {
+    fn paint(&self, out: &mut String, style: impl Fn(&ColorTheme) -> Style, text: &str) {
+        match &self.theme {
+            Some(theme) => style(theme).paint(out, text, theme.depth),
+            None => out.push_str(text),
+        }
+    }
+
+    fn write_value(&mut self, style: impl Fn(&ColorTheme) -> Style, text: &str) {
+        let mut value = mem::take(&mut self.line.value);
+        self.paint(&mut value, style, text);
+        self.line.value = value;
+    }
+}
+
+// The key buffer holds the label of each node on the path, each followed by a newline.
+// Labels never contain newlines, since keys that are not identifiers are quoted.
+impl<W: Write> Formatter<W> for TreeFormatter {
+    fn write_root(&mut self, key: &mut String, ns_root: &str) {
+        self.paint(key, |t| t.key, ns_root);
+        key.push('\n');
+    }
+
+    fn write_key_segment(&mut self, key: &mut String, ns: &NamespaceKey) {
+        match ns {
+            NamespaceKey::Array(n) => {
+                self.paint(key, |t| t.bracket, "[");
+                self.paint(key, |t| t.number, &n.to_string());
+                self.paint(key, |t| t.bracket, "]");
+            }
+            NamespaceKey::Object(k) if is_identifier(k) => self.paint(key, |t| t.key, k),
+            NamespaceKey::Object(k) => {
+                let mut quoted = String::new();
+                write_json_string(k, |s| quoted.write_str(s)).unwrap();
+                self.paint(key, |t| t.string, &quoted);
+            }
+        }
+        key.push('\n');
+    }
+
+    fn end_document(&mut self, writer: &mut W) -> Result<(), Error> {
+        let nodes = mem::take(&mut self.nodes);
+
+        // Walk backwards to find the last child of each parent: a node is the last one unless
+        // a sibling follows before the parent's subtree ends.
+        let mut last = Vec::with_capacity(nodes.len());
+        let mut followed = Vec::new();
+        for node in nodes.iter().rev() {
+            followed.resize(node.depth + 1, false);
+            last.push(!followed[node.depth]);
+            followed[node.depth] = true;
+        }
+        last.reverse();
+
+        // Whether the ancestors of the current node at each depth below it were last children.
+        let mut open: Vec<bool> = Vec::new();
+        let mut s = String::new();
+        for (i, node) in nodes.iter().enumerate() {
+            s.clear();
+            open.truncate(node.depth.saturating_sub(1));
+            for &ancestor_last in &open {
+                s.push_str(if ancestor_last { "    " } else { "│   " });
+            }
+            if node.depth > 0 {
+                s.push_str(if last[i] { "└── " } else { "├── " });
+                open.push(last[i]);
+            }
+            s.push_str(&node.label);
+            let has_children = nodes.get(i + 1).is_some_and(|next| next.depth > node.depth);
+            if !(node.container && has_children) {
+                s.push_str(": ");
+                s.push_str(&node.value);
+            }
+            s.push('\n');
+            writer.write_str(&s)?;
+        }
+        Ok(())
+    }
+
+    fn write_key(&mut self, _writer: &mut W, key: &str) -> Result<(), Error> {
+        let mut labels = key.split_terminator('\n');
+        self.line.label = labels.next_back().unwrap_or_default().to_string();
+        self.line.depth = labels.count();
+        Ok(())
+    }
+
+    fn write_key_value_delimiter(&mut self, _writer: &mut W) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_end_of_line(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.nodes.push(mem::take(&mut self.line));
+        Ok(())
+    }
+
+    fn write_null(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.write_value(|t| t.null, "null");
+        Ok(())
+    }
+
+    fn write_bool(&mut self, _writer: &mut W, value: bool) -> Result<(), Error> {
+        self.write_value(|t| t.bool, &value.to_string());
+        Ok(())
+    }
+
+    fn write_number<N: num::Num + Display>(
+        &mut self,
+        _writer: &mut W,
+        value: N,
+    ) -> Result<(), Error> {
+        self.write_value(|t| t.number, &value.to_string());
+        Ok(())
+    }
+
+    fn write_string(&mut self, _writer: &mut W, value: &str) -> Result<(), Error> {
+        let mut quoted = String::new();
+        write_json_string(value, |s| quoted.write_str(s)).unwrap();
+        self.write_value(|t| t.string, &quoted);
+        Ok(())
+    }
+
+    fn write_init_array(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.line.container = true;
+        self.write_value(|t| t.bracket, "[]");
+        Ok(())
+    }
+
+    fn write_init_object(&mut self, _writer: &mut W) -> Result<(), Error> {
+        self.line.container = true;
+        self.write_value(|t| t.bracket, "{}");
+        Ok(())
+    }
+
+    fn write_json(&mut self, _writer: &mut W, value: &Value) -> Result<(), Error> {
+        self.line.value = value.to_string();
+        Ok(())
+    }
+}
+
+pub fn to_tree_string(value: &impl Serialize, options: &Options) -> Result<String, Error> {
+    let mut s = String::new();
+    to_tree_writer(value, FmtWriter(&mut s), options)?;
+    Ok(s)
+}
+
+/// Writes `value` as an indented tree, one node per line, for reading in a terminal.
+///
+/// Objects and arrays with members are written as just their key, with their members below
+/// them. Colors follow the format type and theme of `options`; the line syntax, alignment and
+/// leaf-only options are ignored, since every container is drawn.
+///
+/// ```
+/// use serde_gron::Options;
+/// use serde_json::json;
+///
+/// let value = json!({ "users": [{ "name": "x" }], "v": 1 });
+/// assert_eq!(
+///     serde_gron::to_tree_string(&value, &Options::default()).unwrap(),
+///     "json\n├── users\n│   └── [0]\n│       └── name: \"x\"\n└── v: 1\n"
+/// );
+/// ```
+pub fn to_tree_writer(
+    value: &impl Serialize,
+    writer: impl Write,
+    options: &Options,
+) -> Result<(), Error> {
+    let formatter = TreeFormatter {
+        theme: options
+            .format_type
+            .is_colored(writer.is_terminal())
+            .then_some(options.theme),
+        ..TreeFormatter::default()
+    };
+    let options = Options {
+        leaf_only: false,
+        ..options.clone()
+    };
+    serialize(value, writer, formatter, &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::FormatType;
+
+    #[test]
+    fn test_tree() {
+        let value = json!({
+            "a": [1.5, { "b-c": "d\n" }, []],
+            "e": { "f": false, "g": null },
+            "h": {}
+        });
+        let options = Options {
+            leaf_only: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            to_tree_string(&value, &options).unwrap(),
+            r#"json
+├── a
+│   ├── [0]: 1.5
+│   ├── [1]
+│   │   └── "b-c": "d\n"
+│   └── [2]: []
+├── e
+│   ├── f: false
+│   └── g: null
+└── h: {}
+"#
+        );
+        assert_eq!(to_tree_string(&1, &options).unwrap(), "json: 1\n");
+    }
+
+    #[test]
+    fn test_tree_collapsed_and_colored() {
+        let options = Options {
+            format_type: FormatType::Color,
+            collapse_depth: Some(1),
+            ..Options::default()
+        };
+        assert_eq!(
+            to_tree_string(&json!({ "a": [1] }), &options).unwrap(),
+            "\x1b[34mjson\x1b[0m\n└── \x1b[34ma\x1b[0m: [1]\n"
+        );
+    }
+}
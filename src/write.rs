@@ -0,0 +1,208 @@
+use core::fmt;
+
+use crate::Error;
+
+/// The output the serializer writes to.
+///
+/// With the `std` feature this is implemented for every [`std::io::Write`]. Wrap a
+/// [`core::fmt::Write`], such as a `String`, in [`FmtWriter`].
+pub trait Write {
+    fn write_str(&mut self, s: &str) -> Result<(), Error>;
+
+    /// Whether the output is shown on a terminal, which [`FormatType::Auto`] colors.
+    ///
+    /// Defaults to `false`, including for every [`std::io::Write`]; wrap one in [`Terminal`]
+    /// to say otherwise.
+    ///
+    /// [`FormatType::Auto`]: crate::FormatType::Auto
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), Error> {
+        struct Adapter<'a, W: ?Sized> {
+            inner: &'a mut W,
+            error: Option<Error>,
+        }
+
+        impl<W: Write + ?Sized> fmt::Write for Adapter<'_, W> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.inner.write_str(s).map_err(|e| {
+                    self.error = Some(e);
+                    fmt::Error
+                })
+            }
+        }
+
+        let mut adapter = Adapter {
+            inner: self,
+            error: None,
+        };
+        fmt::write(&mut adapter, args).map_err(|_| adapter.error.unwrap_or(Error::Fmt))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> Write for W {
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.write_all(s.as_bytes()).map_err(Error::Io)
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), Error> {
+        std::io::Write::write_fmt(self, args).map_err(Error::Io)
+    }
+}
+
+/// Adapts a [`core::fmt::Write`] to [`Write`].
+///
+/// ```
+/// use serde_gron::FmtWriter;
+///
+/// let mut s = String::new();
+/// serde_gron::to_writer(&1, FmtWriter(&mut s)).unwrap();
+/// assert_eq!(s, "json = 1;\n");
+/// ```
+#[derive(Debug)]
+pub struct FmtWriter<W>(pub W);
+
+impl<W: fmt::Write> Write for FmtWriter<W> {
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.0.write_str(s).map_err(|_| Error::Fmt)
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), Error> {
+        self.0.write_fmt(args).map_err(|_| Error::Fmt)
+    }
+}
+
+/// Marks whether a [`std::io::Write`] is a terminal, for [`FormatType::Auto`].
+///
+/// ```
+/// use serde_gron::{FormatType, Terminal};
+///
+/// let mut out = vec![];
+/// let writer = Terminal::new(&mut out, false);
+/// serde_gron::to_writer_with(&1, writer, "json", FormatType::Auto).unwrap();
+/// assert_eq!(out, b"json = 1;\n");
+/// ```
+///
+/// [`FormatType::Auto`]: crate::FormatType::Auto
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct Terminal<W> {
+    writer: W,
+    is_terminal: bool,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Terminal<W> {
+    pub fn new(writer: W, is_terminal: bool) -> Self {
+        Terminal {
+            writer,
+            is_terminal,
+        }
+    }
+
+    /// Asks `writer` whether it is a terminal, as for `std::io::stdout()`.
+    pub fn detect(writer: W) -> Self
+    where
+        W: std::io::IsTerminal,
+    {
+        let is_terminal = writer.is_terminal();
+        Terminal::new(writer, is_terminal)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Write for Terminal<W> {
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.writer.write_str(s)
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), Error> {
+        Write::write_fmt(&mut self.writer, args)
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.is_terminal
+    }
+}
+
+/// Passes `value` to `write` as a quoted JSON string, in as few pieces as possible.
+pub(crate) fn write_json_string<E>(
+    value: &str,
+    mut write: impl FnMut(&str) -> Result<(), E>,
+) -> Result<(), E> {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+
+    write("\"")?;
+    let mut start = 0;
+    for (i, b) in value.bytes().enumerate() {
+        let unicode;
+        let escaped = match b {
+            b'"' => "\\\"",
+            b'\\' => "\\\\",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            b'\t' => "\\t",
+            0x08 => "\\b",
+            0x0c => "\\f",
+            0x00..=0x1f => {
+                unicode = [
+                    b'\\',
+                    b'u',
+                    b'0',
+                    b'0',
+                    HEX[(b >> 4) as usize],
+                    HEX[(b & 0xf) as usize],
+                ];
+                core::str::from_utf8(&unicode).unwrap()
+            }
+            _ => continue,
+        };
+        if start < i {
+            write(&value[start..i])?;
+        }
+        write(escaped)?;
+        start = i + 1;
+    }
+    if start < value.len() {
+        write(&value[start..])?;
+    }
+    write("\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::*;
+
+    fn escape(value: &str) -> String {
+        let mut s = String::new();
+        write_json_string(value, |part| {
+            s.push_str(part);
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+        s
+    }
+
+    #[test]
+    fn test_write_json_string() {
+        for value in [
+            "",
+            "abc",
+            "a\"b\\c",
+            "\n\r\t\u{8}\u{c}",
+            "\u{1}\u{1f}",
+            "日本語\u{7f}",
+        ] {
+            assert_eq!(escape(value), serde_json::to_string(value).unwrap());
+        }
+    }
+}